@@ -1,6 +1,7 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use rfgrep::search_algorithms::SearchAlgorithm;
 use rfgrep::streaming_search::{StreamingConfig, StreamingSearchPipeline};
+use rfgrep::walker::{walk_dir_parallel, walk_dir_with, WalkOptions};
 
 use std::fs;
 use std::path::Path;
@@ -24,6 +25,9 @@ fn criterion_benchmark(c: &mut Criterion) {
 
     // Benchmark memory usage
     benchmark_memory_usage(c, &test_dir);
+
+    // Benchmark walking a tree and searching it, serially and overlapped
+    benchmark_walk(c);
 }
 
 fn generate_test_data(test_dir: &Path) {
@@ -78,6 +82,7 @@ fn benchmark_algorithms(c: &mut Criterion, test_dir: &Path) {
             timeout_per_file: None,
             chunk_size: 8192,
             buffer_size: 65536,
+            binary_strings: None,
         };
 
         let pipeline = StreamingSearchPipeline::new(config);
@@ -124,6 +129,7 @@ fn benchmark_file_sizes(c: &mut Criterion, test_dir: &Path) {
             timeout_per_file: None,
             chunk_size: 8192,
             buffer_size: 65536,
+            binary_strings: None,
         };
 
         let pipeline = StreamingSearchPipeline::new(config);
@@ -166,6 +172,7 @@ fn benchmark_patterns(c: &mut Criterion, test_dir: &Path) {
             timeout_per_file: None,
             chunk_size: 8192,
             buffer_size: 65536,
+            binary_strings: None,
         };
 
         let pipeline = StreamingSearchPipeline::new(config);
@@ -203,6 +210,7 @@ fn benchmark_memory_usage(c: &mut Criterion, test_dir: &Path) {
         timeout_per_file: None,
         chunk_size: 8192,
         buffer_size: 65536,
+        binary_strings: None,
     };
 
     let pipeline = StreamingSearchPipeline::new(config);
@@ -226,5 +234,76 @@ fn benchmark_memory_usage(c: &mut Criterion, test_dir: &Path) {
     });
 }
 
+/// A tree of many small files, where the walk dominates the search time
+fn generate_tree(root: &Path) {
+    for d in 0..50 {
+        let dir = root.join(format!("dir_{}", d)).join("nested");
+        fs::create_dir_all(&dir).unwrap();
+        for f in 0..100 {
+            let content = format!("line one\nfile {} in dir {}\npattern1 here\n", f, d);
+            fs::write(dir.join(format!("file_{}.txt", f)), content).unwrap();
+        }
+    }
+}
+
+fn benchmark_walk(c: &mut Criterion) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path().to_path_buf();
+    generate_tree(&root);
+
+    let config = StreamingConfig {
+        algorithm: SearchAlgorithm::BoyerMoore,
+        context_lines: 0,
+        case_sensitive: true,
+        invert_match: false,
+        max_matches: None,
+        timeout_per_file: None,
+        chunk_size: 8192,
+        buffer_size: 65536,
+        binary_strings: None,
+    };
+    let pipeline = StreamingSearchPipeline::new(config);
+    let options = WalkOptions::default();
+    let threads = num_cpus::get().min(8);
+    let rt = tokio::runtime::Runtime::new().unwrap();
+
+    // Walk the whole tree, then search the collected files
+    c.bench_function("walk_and_search/serial", |b| {
+        b.iter(|| {
+            let files: Vec<_> = walk_dir_with(&root, true, &options)
+                .unwrap()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_file())
+                .map(|entry| entry.into_path())
+                .collect();
+            rt.block_on(async {
+                let mut rx = pipeline.search_files_streaming(files, "pattern1", threads);
+                while rx.recv().await.is_some() {}
+            });
+        });
+    });
+
+    // Walk on several threads, feeding files to the searchers as they are found
+    c.bench_function("walk_and_search/parallel", |b| {
+        b.iter(|| {
+            let walker = walk_dir_parallel(&root, true, &options, threads).unwrap();
+            let (tx, paths) = tokio::sync::mpsc::channel(threads * 64);
+            let walk = std::thread::spawn(move || {
+                walker.run(|entry| match entry {
+                    Ok(entry) if entry.path().is_file() => {
+                        tx.blocking_send(entry.into_path()).is_ok()
+                    }
+                    _ => true,
+                })
+            });
+            rt.block_on(async {
+                let mut rx = pipeline.search_paths_streaming(paths, "pattern1", threads);
+                while rx.recv().await.is_some() {}
+            });
+            walk.join().unwrap();
+        });
+    });
+}
+
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
@@ -1,4 +1,5 @@
 //! Main application structure and command handling
+use crate::app_simple;
 use crate::cli::{Cli, Commands};
 use crate::error::{Result as RfgrepResult, RfgrepError};
 use crate::search::SearchEngine;
@@ -6,13 +7,12 @@ use crate::list::ListEngine;
 use crate::interactive::InteractiveEngine;
 use crate::output::OutputManager;
 use crate::metrics::Metrics;
+use crate::walker::WalkOptions;
 use std::sync::Arc;
 
 /// Main application struct that coordinates all components
 pub struct RfgrepApp {
     search_engine: SearchEngine,
-    list_engine: ListEngine,
-    interactive_engine: InteractiveEngine,
     output_manager: OutputManager,
     metrics: Arc<Metrics>,
 }
@@ -22,14 +22,10 @@ impl RfgrepApp {
     pub fn new() -> RfgrepResult<Self> {
         let metrics = Arc::new(Metrics::new());
         let search_engine = SearchEngine::new(metrics.clone())?;
-        let list_engine = ListEngine::new(metrics.clone())?;
-        let interactive_engine = InteractiveEngine::new(metrics.clone())?;
         let output_manager = OutputManager::new()?;
 
         Ok(Self {
             search_engine,
-            list_engine,
-            interactive_engine,
             output_manager,
             metrics,
         })
@@ -153,6 +149,7 @@ impl RfgrepApp {
     }
 
     async fn handle_list(&self, cli: Cli) -> RfgrepResult<()> {
+        let walk = walk_options(&cli)?;
         if let Commands::List {
             extensions,
             long,
@@ -178,7 +175,8 @@ impl RfgrepApp {
                 .or_else(|| cmd_path.as_ref().map(|p| p.as_path()))
                 .unwrap_or(&cli.path);
 
-            let files = self.list_engine.list_files(
+            let list_engine = ListEngine::new(self.metrics.clone())?.with_walk_options(walk);
+            let files = list_engine.list_files(
                 list_root,
                 *recursive,
                 *show_hidden,
@@ -191,23 +189,24 @@ impl RfgrepApp {
             ).await?;
 
             if *long {
-                self.list_engine.print_long_format(&files);
+                list_engine.print_long_format(&files);
             } else {
-                self.list_engine.print_simple_list(&files);
+                list_engine.print_simple_list(&files);
             }
 
             if *stats {
-                self.list_engine.print_statistics(&files);
+                list_engine.print_statistics(&files);
             }
 
             if *copy {
-                self.list_engine.copy_to_clipboard(&files)?;
+                list_engine.copy_to_clipboard(&files)?;
             }
         }
         Ok(())
     }
 
     async fn handle_interactive(&self, cli: Cli) -> RfgrepResult<()> {
+        let walk = walk_options(&cli)?;
         if let Commands::Interactive {
             pattern,
             algorithm,
@@ -223,7 +222,9 @@ impl RfgrepApp {
                 .or_else(|| cmd_path.as_ref().map(|p| p.as_path()))
                 .unwrap_or(&cli.path);
 
-            self.interactive_engine.run(
+            let mut interactive_engine =
+                InteractiveEngine::new(self.metrics.clone())?.with_walk_options(walk);
+            interactive_engine.run(
                 interactive_root,
                 pattern,
                 *algorithm,
@@ -262,6 +263,12 @@ impl RfgrepApp {
     }
 }
 
+/// Walk settings from the global options and the `[ignore]` section of the loaded config
+fn walk_options(cli: &Cli) -> RfgrepResult<WalkOptions> {
+    let config = app_simple::load_config(cli)?;
+    app_simple::walk_options(cli, &config.ignore)
+}
+
 impl Default for RfgrepApp {
     fn default() -> Self {
         Self::new().expect("Failed to create RfgrepApp")
@@ -1,24 +1,51 @@
 //! Simplified application structure
+use crate::binary::HexPattern;
+use crate::budget::{BudgetTracker, ResourceBudget};
+use crate::cancel::CancellationToken;
 use crate::cli::{
-    Cli, Commands, PluginCommands, SearchAlgorithm as CliSearchAlgorithm, SearchMode,
+    Cli, Commands, ConfigCommands, MatchSort, PluginCommands, SavedCommands,
+    SearchAlgorithm as CliSearchAlgorithm, SearchMode,
+};
+use crate::config::{
+    config_sources, user_config_path, Config, IgnoreConfig, CONFIG_TEMPLATE, PROJECT_CONFIG,
 };
 use crate::error::{Result as RfgrepResult, RfgrepError};
-use crate::file_types::{FileTypeClassifier, SearchDecision};
+use crate::file_filters::{modified_time, resolve_owner, FileFilters};
+use crate::file_types::{type_definitions, FileTypeClassifier, SearchDecision};
+use crate::git_history::{is_binary_blob, HistoryChange, Repository, RevisionMatch, RevisionScope};
+use crate::git_scope::GitScope;
 use crate::output_formats::OutputFormatter;
 use crate::plugin_cli::PluginCli;
-use crate::plugin_system::{EnhancedPluginManager, PluginRegistry};
-use crate::processor::search_file;
+use crate::plugin_system::{EnhancedBinarySearchPlugin, EnhancedPluginManager, PluginRegistry};
+use crate::processor::{search_file, SearchMatch};
+use crate::saved_searches::{
+    remove_search, save_search, user_saved_path, SavedSearch, SavedSearches, SavedSource,
+    PROJECT_SAVED_FILE,
+};
 use crate::search_algorithms::SearchAlgorithm;
 use crate::streaming_search::{StreamingConfig, StreamingSearchPipeline};
+use crate::summary::{ExitStatus, SearchSummary, SkipReason};
 use crate::tui::{init_terminal, restore_terminal, TuiApp};
-use crate::walker::walk_dir;
+use crate::walker::{
+    filter_listed, is_stdin_path, read_path_list, walk_dir_parallel, walk_dir_with, walk_roots,
+    PathFilter, SeenFiles, WalkOptions,
+};
 use colored::Colorize;
-use std::path::Path;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+
+/// Path reported for matches read from standard input
+const STDIN_LABEL: &str = "(standard input)";
 
 /// Simplified application that uses existing components
 pub struct RfgrepApp {
     plugin_manager: Arc<EnhancedPluginManager>,
+    cancel: CancellationToken,
 }
 
 impl RfgrepApp {
@@ -32,7 +59,10 @@ impl RfgrepApp {
             Ok(handle) => {
                 // We're already in an async context, use the current runtime
                 handle.block_on(async { registry.load_plugins().await })?;
-                return Ok(Self { plugin_manager });
+                return Ok(Self {
+                    plugin_manager,
+                    cancel: CancellationToken::new(),
+                });
             }
             Err(_) => {
                 // No current runtime, create a new one
@@ -43,7 +73,10 @@ impl RfgrepApp {
         };
 
         rt.block_on(async { registry.load_plugins().await })?;
-        Ok(Self { plugin_manager })
+        Ok(Self {
+            plugin_manager,
+            cancel: CancellationToken::new(),
+        })
     }
 
     /// Create a new application instance with async support
@@ -54,22 +87,48 @@ impl RfgrepApp {
         // Load plugins asynchronously
         registry.load_plugins().await?;
 
-        Ok(Self { plugin_manager })
+        Ok(Self {
+            plugin_manager,
+            cancel: CancellationToken::new(),
+        })
+    }
+
+    /// Stop walking and searching when `cancel` fires (e.g. from a Ctrl-C handler);
+    /// results found so far are still written and the run exits with status 130
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
     }
 
-    /// Run the application with the given CLI arguments
-    pub async fn run(&self, cli: Cli) -> RfgrepResult<()> {
+    /// Run the application with the given CLI arguments, returning the process exit status
+    pub async fn run(&self, cli: Cli) -> RfgrepResult<ExitStatus> {
         if let Some(log_path) = &cli.log {
             std::fs::write(log_path, "rfgrep log file created\n").map_err(RfgrepError::Io)?;
         }
 
+        // Handled before loading the config, so a broken file can be reported
+        if let Commands::Config { command } = &cli.command {
+            self.handle_config(command, &cli)?;
+            return Ok(ExitStatus::Success);
+        }
+        if let Commands::Saved { command } = &cli.command {
+            return self.handle_saved(command, &cli).await;
+        }
+
         let is_piped = !is_terminal::is_terminal(&std::io::stdout());
         let quiet = cli.quiet || is_piped;
+        let config = load_config(&cli)?;
+        let walk = walk_options(&cli, &config.ignore)?;
+        let skip_binary = cli.skip_binary || config.ignore.binary_files;
+        let classifier = FileTypeClassifier::new().with_config(&config.file_types);
 
         match &cli.command {
             Commands::Search {
                 pattern,
                 mode,
+                hex,
+                binary_strings,
+                min_string_len,
                 algorithm,
                 recursive,
                 context_lines,
@@ -77,6 +136,12 @@ impl RfgrepApp {
                 invert_match,
                 max_matches,
                 timeout_per_file,
+                sort,
+                deadline,
+                max_bytes,
+                max_files,
+                max_memory,
+                max_time,
                 path: cmd_path,
                 path_flag: cmd_path_flag,
                 output_format,
@@ -88,11 +153,43 @@ impl RfgrepApp {
                 ndjson,
                 count,
                 files_with_matches,
+                show_skipped,
+                files_from,
+                null_data,
+                git_changed,
+                git_staged,
+                git_untracked,
+                git_tracked_only,
+                rev,
+                history,
                 ..
             } => {
+                let git = GitScope {
+                    changed: git_changed.clone(),
+                    staged: *git_staged,
+                    untracked: *git_untracked,
+                    tracked: *git_tracked_only,
+                };
+                let revisions = RevisionScope {
+                    rev: rev.clone(),
+                    history: *history,
+                };
+                let budget = ResourceBudget {
+                    max_bytes: *max_bytes,
+                    max_files: *max_files,
+                    max_memory_mb: *max_memory,
+                    max_time: *max_time,
+                }
+                .or(ResourceBudget::for_policy(
+                    &cli.safety_policy,
+                    &config.performance,
+                ));
+                let budget = Arc::new(BudgetTracker::new(budget, &config.performance));
                 self.handle_search(
                     pattern,
                     mode.clone(),
+                    *hex,
+                    binary_strings.then_some(*min_string_len),
                     algorithm.clone(),
                     *recursive,
                     *context_lines,
@@ -100,15 +197,15 @@ impl RfgrepApp {
                     *invert_match,
                     *max_matches,
                     *timeout_per_file,
-                    cmd_path
-                        .as_ref()
-                        .or(cmd_path_flag.as_ref())
-                        .map(|p| p.as_path())
-                        .unwrap_or(&cli.path),
+                    *sort,
+                    *deadline,
+                    &budget,
+                    &search_roots(cmd_path, cmd_path_flag, &cli.path),
                     cli.max_size,
-                    cli.skip_binary,
+                    skip_binary,
                     output_format.clone(),
                     file_types.clone(),
+                    &classifier,
                     include_extensions.clone(),
                     exclude_extensions.clone(),
                     *search_all_files,
@@ -118,10 +215,42 @@ impl RfgrepApp {
                     *ndjson,
                     *count,
                     *files_with_matches,
+                    *show_skipped,
+                    files_from.as_deref(),
+                    *null_data,
+                    &git,
+                    &revisions,
+                    &walk.or_defaults(true, false),
                     quiet,
+                    cli.dry_run,
                 )
                 .await
             }
+            command => {
+                self.run_command(command, &cli, &walk, skip_binary, &classifier)
+                    .await?;
+                Ok(if self.cancel.is_cancelled() {
+                    ExitStatus::Interrupted
+                } else {
+                    ExitStatus::Success
+                })
+            }
+        }
+    }
+
+    /// Run a command other than `search`
+    async fn run_command(
+        &self,
+        command: &Commands,
+        cli: &Cli,
+        walk: &WalkOptions,
+        skip_binary: bool,
+        classifier: &FileTypeClassifier,
+    ) -> RfgrepResult<()> {
+        match command {
+            Commands::Search { .. } => unreachable!("search is handled by run"),
+            Commands::Config { .. } => unreachable!("config is handled by run"),
+            Commands::Saved { .. } => unreachable!("saved is handled by run"),
             Commands::List {
                 extensions,
                 long,
@@ -155,9 +284,8 @@ impl RfgrepApp {
                     *limit,
                     *copy,
                     output_format.clone(),
-                    cmd_path.as_ref().map(|p| p.as_path()),
-                    cmd_path_flag.as_ref().map(|p| p.as_path()),
-                    &cli.path,
+                    &search_roots(cmd_path, cmd_path_flag, &cli.path),
+                    walk,
                 )
                 .await
             }
@@ -166,6 +294,8 @@ impl RfgrepApp {
                 Ok(())
             }
             Commands::Completions { shell } => self.handle_completions(*shell),
+            Commands::Types {} => self.handle_types(&cli.type_add),
+            Commands::Explain { paths } => self.handle_explain(paths, classifier),
             Commands::Simulate {} => {
                 use std::fs;
                 use std::time::Instant;
@@ -234,7 +364,9 @@ impl RfgrepApp {
 
                     let regex = crate::processor::get_or_compile_regex(&pat)?;
                     for f in &files {
-                        if let Ok(matches) = crate::processor::search_file(f, &regex) {
+                        if let Ok(matches) =
+                            crate::processor::search_file_with(f, &regex, cli.follow)
+                        {
                             total += matches.len();
                             files_processed += 1;
                         }
@@ -269,6 +401,8 @@ impl RfgrepApp {
                     mode,
                     *context_lines,
                     path,
+                    &walk.or_defaults(false, true),
+                    skip_binary,
                 )
                 .await
             }
@@ -279,6 +413,8 @@ impl RfgrepApp {
         &self,
         pattern: &str,
         mode: crate::cli::SearchMode,
+        hex: bool,
+        binary_strings: Option<usize>,
         algorithm: CliSearchAlgorithm,
         recursive: bool,
         context_lines: usize,
@@ -286,11 +422,15 @@ impl RfgrepApp {
         invert_match: bool,
         max_matches: Option<usize>,
         timeout_per_file: Option<u64>,
-        search_path: &Path,
+        sort: Option<MatchSort>,
+        deadline: Option<Duration>,
+        budget: &Arc<BudgetTracker>,
+        search_paths: &[PathBuf],
         max_size: Option<usize>,
-        _skip_binary: bool,
+        skip_binary: bool,
         output_format: crate::cli::OutputFormat,
         file_types: crate::cli::FileTypeStrategy,
+        classifier: &FileTypeClassifier,
         include_extensions: Option<Vec<String>>,
         exclude_extensions: Option<Vec<String>>,
         search_all_files: bool,
@@ -300,54 +440,282 @@ impl RfgrepApp {
         ndjson: bool,
         count: bool,
         files_with_matches: bool,
+        show_skipped: bool,
+        files_from: Option<&Path>,
+        null_data: bool,
+        git: &GitScope,
+        revisions: &RevisionScope,
+        walk: &WalkOptions,
         quiet: bool,
-    ) -> RfgrepResult<()> {
-        let search_pattern = self.build_search_pattern(pattern, mode);
+        dry_run: bool,
+    ) -> RfgrepResult<ExitStatus> {
+        check_stdin_root(search_paths)?;
+        let summary = Arc::new(SearchSummary::new().with_show_skipped(show_skipped));
+        let deadline = deadline
+            .into_iter()
+            .chain(budget.budget().max_time)
+            .min()
+            .map(|deadline| Instant::now() + deadline);
+
+        if hex {
+            let hex_pattern = HexPattern::parse(pattern)?;
+            let plugin = EnhancedBinarySearchPlugin::new().with_hexdump_rows(context_lines);
+
+            let all_matches = if files_from.is_none() && reads_stdin(search_paths) {
+                let mut data = Vec::new();
+                std::io::stdin()
+                    .read_to_end(&mut data)
+                    .map_err(RfgrepError::Io)?;
+                summary.file_searched();
+                plugin.search_hex_in(&data, Path::new(STDIN_LABEL), &hex_pattern)
+            } else {
+                let files = self.source_files(
+                    search_paths,
+                    recursive,
+                    files_from,
+                    null_data,
+                    git,
+                    walk,
+                    deadline,
+                    &summary,
+                )?;
+                // Binary files are the point of a byte search, so type classification is bypassed
+                let selection = FileSelection {
+                    max_size,
+                    skip_binary: false,
+                    safety_policy,
+                    include_extensions,
+                    exclude_extensions,
+                    search_all_files: true,
+                    text_only: false,
+                    file_types,
+                    classifier: classifier.clone(),
+                };
+                let filtered_files = selection.filter(files, &summary);
+                if dry_run {
+                    return self.list_dry_run(&filtered_files, &summary);
+                }
+                self.perform_hex_search(
+                    &plugin,
+                    &filtered_files,
+                    &hex_pattern,
+                    max_matches,
+                    deadline,
+                    budget,
+                    &summary,
+                )
+            };
+
+            return self.output_results(
+                &all_matches,
+                pattern,
+                search_paths,
+                output_format,
+                ndjson,
+                count,
+                files_with_matches,
+                null_data,
+                quiet,
+                &summary,
+            );
+        }
+
+        let search_pattern = self.build_search_pattern(pattern, mode.clone());
         let search_algorithm = self.map_search_algorithm(algorithm);
+        let config = self.build_streaming_config(
+            search_algorithm,
+            context_lines,
+            case_sensitive,
+            invert_match,
+            max_matches,
+            timeout_per_file,
+            binary_strings,
+        );
+        let pipeline = self
+            .build_pipeline(config, deadline, &summary)
+            .with_budget(budget.clone());
 
-        let files = self.collect_files(search_path, recursive);
-        let filtered_files = self.filter_files(
-            files,
+        // Strings mode exists to look inside executables, so type classification is bypassed
+        let selection = FileSelection {
             max_size,
-            _skip_binary,
+            skip_binary: skip_binary && binary_strings.is_none(),
             safety_policy,
             include_extensions,
             exclude_extensions,
-            search_all_files,
+            search_all_files: search_all_files || binary_strings.is_some(),
             text_only,
             file_types,
-        );
+            classifier: classifier.clone(),
+        };
+
+        if !revisions.is_empty() {
+            let mut all_matches = Vec::new();
+            for root in search_paths {
+                all_matches.extend(
+                    self.search_revisions(
+                        &pipeline,
+                        pattern,
+                        &search_pattern,
+                        mode.clone(),
+                        case_sensitive,
+                        revisions,
+                        root,
+                        walk,
+                        &selection,
+                        deadline,
+                        budget,
+                        &summary,
+                    )
+                    .await?,
+                );
+            }
+
+            return self.output_results(
+                &all_matches,
+                pattern,
+                search_paths,
+                output_format,
+                ndjson,
+                count,
+                files_with_matches,
+                null_data,
+                quiet,
+                &summary,
+            );
+        }
+
+        if files_from.is_none() && reads_stdin(search_paths) {
+            let all_matches = if binary_strings.is_some() {
+                let mut data = Vec::new();
+                std::io::stdin()
+                    .read_to_end(&mut data)
+                    .map_err(RfgrepError::Io)?;
+                pipeline
+                    .search_strings_in(&data, &search_pattern, Path::new(STDIN_LABEL))
+                    .await?
+            } else {
+                pipeline
+                    .search_reader(std::io::stdin(), &search_pattern, Path::new(STDIN_LABEL))
+                    .await?
+            };
+
+            return self.output_results(
+                &all_matches,
+                pattern,
+                search_paths,
+                output_format,
+                ndjson,
+                count,
+                files_with_matches,
+                null_data,
+                quiet,
+                &summary,
+            );
+        }
 
-        if !quiet && output_format != crate::cli::OutputFormat::Json && !ndjson {
+        let thread_count = threads.unwrap_or_else(|| num_cpus::get().min(8));
+        let formatter = self.build_formatter(output_format.clone(), ndjson);
+        let streaming =
+            sort.is_none() && (count || files_with_matches || formatter.supports_streaming());
+
+        if streaming && !dry_run && files_from.is_none() && git.is_empty() {
+            // Walking, classification and matching overlap: files go to the search
+            // workers as soon as they are found instead of after the whole walk
+            let files = self.walk_parallel(
+                search_paths,
+                recursive,
+                walk,
+                selection,
+                thread_count,
+                deadline,
+                budget.clone(),
+                &summary,
+            )?;
+            let results = pipeline.search_paths_streaming(files, &search_pattern, thread_count);
+            return self
+                .stream_results(
+                    results,
+                    pattern,
+                    search_paths,
+                    formatter,
+                    ndjson,
+                    count,
+                    files_with_matches,
+                    null_data,
+                    &summary,
+                )
+                .await;
+        }
+
+        let files = self.source_files(
+            search_paths,
+            recursive,
+            files_from,
+            null_data,
+            git,
+            walk,
+            deadline,
+            &summary,
+        )?;
+        let filtered_files = selection.filter(files, &summary);
+        if dry_run {
+            return self.list_dry_run(&filtered_files, &summary);
+        }
+
+        if !quiet && !output_format.is_structured() && !ndjson {
             println!("Searching {} files...", filtered_files.len());
         }
 
-        let all_matches = self
-            .perform_search(
-                &filtered_files,
-                &search_pattern,
-                search_algorithm,
-                context_lines,
-                case_sensitive,
-                invert_match,
-                max_matches,
-                timeout_per_file,
-                threads,
-            )
+        if streaming {
+            let results =
+                pipeline.search_files_streaming(filtered_files, &search_pattern, thread_count);
+            return self
+                .stream_results(
+                    results,
+                    pattern,
+                    search_paths,
+                    formatter,
+                    ndjson,
+                    count,
+                    files_with_matches,
+                    null_data,
+                    &summary,
+                )
+                .await;
+        }
+
+        let mut all_matches = self
+            .perform_search(&pipeline, &filtered_files, &search_pattern, thread_count)
             .await?;
+        if sort == Some(MatchSort::Path) {
+            all_matches.sort();
+        }
 
         self.output_results(
             &all_matches,
             pattern,
-            search_path,
+            search_paths,
             output_format,
             ndjson,
             count,
             files_with_matches,
+            null_data,
             quiet,
+            &summary,
         )
     }
 
+    /// Print the files a `--dry-run` would search, without reading them
+    fn list_dry_run(&self, files: &[PathBuf], summary: &SearchSummary) -> RfgrepResult<ExitStatus> {
+        let listing: String = files
+            .iter()
+            .map(|path| format!("{}\n", path.display()))
+            .collect();
+        write_stdout(&listing)?;
+        eprint!("{}", summary.report());
+        Ok(ExitStatus::Success)
+    }
+
     /// Build search pattern based on mode
     fn build_search_pattern(&self, pattern: &str, mode: crate::cli::SearchMode) -> String {
         match mode {
@@ -366,215 +734,320 @@ impl RfgrepApp {
         }
     }
 
-    /// Collect files from directory
-    fn collect_files(&self, search_path: &Path, recursive: bool) -> Vec<std::path::PathBuf> {
-        let entries: Vec<_> = walk_dir(search_path, recursive, true).collect();
-        entries
-            .into_iter()
-            .filter(|entry| entry.path().is_file())
-            .map(|entry| entry.path().to_path_buf())
-            .collect()
-    }
-
-    /// Filter files based on various criteria
-    fn filter_files(
+    /// Search file contents from git instead of the working tree: the tree of a
+    /// single revision, or with `--history` the lines each commit added or removed.
+    /// Blob paths go through the same path and file selection checks as a walk, and
+    /// the bytes read are charged to the budget.
+    async fn search_revisions(
         &self,
-        files: Vec<std::path::PathBuf>,
-        max_size: Option<usize>,
-        skip_binary: bool,
-        safety_policy: crate::cli::SafetyPolicy,
-        include_extensions: Option<Vec<String>>,
-        exclude_extensions: Option<Vec<String>>,
-        search_all_files: bool,
-        text_only: bool,
-        file_types: crate::cli::FileTypeStrategy,
-    ) -> Vec<std::path::PathBuf> {
-        files
-            .into_iter()
-            .filter(|path| {
-                self.should_search_file(
-                    path,
-                    max_size,
-                    skip_binary,
-                    &safety_policy,
-                    &include_extensions,
-                    &exclude_extensions,
-                    search_all_files,
-                    text_only,
-                    &file_types,
-                )
+        pipeline: &StreamingSearchPipeline,
+        pattern: &str,
+        search_pattern: &str,
+        mode: crate::cli::SearchMode,
+        case_sensitive: bool,
+        revisions: &RevisionScope,
+        search_path: &Path,
+        walk: &WalkOptions,
+        selection: &FileSelection,
+        deadline: Option<Instant>,
+        budget: &BudgetTracker,
+        summary: &SearchSummary,
+    ) -> RfgrepResult<Vec<SearchMatch>> {
+        let repo = Repository::open(search_path)?;
+        let tip = repo.resolve(revisions.tip())?;
+        let paths = PathFilter::new(search_path, walk)?;
+        let mut blobs = repo.blob_reader()?;
+        let mut all_matches = Vec::new();
+        let stopped = || {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                summary.not_reached(search_path, "revision search stopped at deadline");
+                return true;
+            }
+            self.cancel.is_cancelled() || budget.exceeded().is_some()
+        };
+        // Reads a blob if its path and size pass the walk filters and file selection
+        let mut read = |object: &str, revision: &RevisionMatch| {
+            blobs.read_if(object, |size| {
+                if !paths.keep_stored(&revision.path, size) {
+                    return false;
+                }
+                match selection.check_stored(&revision.path, size) {
+                    Ok(()) => true,
+                    Err((skip_reason, reason)) => {
+                        summary.filtered(&revision.label(search_path), skip_reason, reason);
+                        false
+                    }
+                }
             })
-            .collect()
-    }
-
-    /// Determine if a file should be searched
-    fn should_search_file(
-        &self,
-        path: &Path,
-        max_size: Option<usize>,
-        skip_binary: bool,
-        safety_policy: &crate::cli::SafetyPolicy,
-        include_extensions: &Option<Vec<String>>,
-        exclude_extensions: &Option<Vec<String>>,
-        search_all_files: bool,
-        text_only: bool,
-        file_types: &crate::cli::FileTypeStrategy,
-    ) -> bool {
-        let metadata = match path.metadata() {
-            Ok(m) => m,
-            Err(_) => return false,
         };
 
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|s| s.to_ascii_lowercase())
-            .unwrap_or_default();
-
-        // Check binary files
-        if skip_binary && crate::processor::is_binary(path) {
-            return false;
+        if !revisions.history {
+            for entry in repo.tree(&tip)? {
+                if stopped() {
+                    break;
+                }
+                let revision = RevisionMatch {
+                    commit: tip.clone(),
+                    path: entry.path,
+                    change: None,
+                };
+                let Some(data) = read(&entry.object, &revision)? else {
+                    continue;
+                };
+                all_matches.extend(
+                    self.search_blob(pipeline, search_pattern, data, &revision, search_path)
+                        .await?,
+                );
+            }
+            return Ok(all_matches);
         }
 
-        // Apply safety policy
-        if !self.apply_safety_policy(safety_policy, &metadata, &ext) {
-            return false;
-        }
+        // Word matches are a subset of the literal ones, so git narrows the commits down
+        // with the plain pattern and the line comparison below applies word boundaries
+        let regex = mode == crate::cli::SearchMode::Regex;
+        for entry in repo.history(&tip, pattern, regex, case_sensitive)? {
+            if stopped() {
+                break;
+            }
+            let added = RevisionMatch {
+                commit: entry.commit.clone(),
+                path: entry.path.clone(),
+                change: Some(HistoryChange::Added),
+            };
+            let removed = RevisionMatch {
+                change: Some(HistoryChange::Removed),
+                ..added.clone()
+            };
 
-        // Check extension filters
-        if !self.apply_extension_filters(&ext, include_extensions, exclude_extensions) {
-            return false;
-        }
+            let mut new = Vec::new();
+            if let Some(object) = &entry.new {
+                if let Some(data) = read(object, &added)? {
+                    new = self
+                        .search_blob(pipeline, search_pattern, data, &added, search_path)
+                        .await?;
+                }
+            }
+            let mut old = Vec::new();
+            if let Some(object) = &entry.old {
+                if let Some(data) = read(object, &removed)? {
+                    old = self
+                        .search_blob(pipeline, search_pattern, data, &removed, search_path)
+                        .await?;
+                }
+            }
 
-        // Check file type strategy
-        if !self.should_search_by_file_type(
-            path,
-            &metadata,
-            &ext,
-            search_all_files,
-            text_only,
-            file_types,
-        ) {
-            return false;
+            all_matches.extend(unmatched_lines(&new, &old));
+            all_matches.extend(unmatched_lines(&old, &new));
         }
+        Ok(all_matches)
+    }
 
-        // Check size limits
-        if !self.apply_size_limits(&metadata, max_size) {
-            return false;
+    /// Search one blob, labelling its matches with the commit and path. Binary blobs
+    /// are skipped.
+    async fn search_blob(
+        &self,
+        pipeline: &StreamingSearchPipeline,
+        search_pattern: &str,
+        data: Vec<u8>,
+        revision: &RevisionMatch,
+        search_path: &Path,
+    ) -> RfgrepResult<Vec<SearchMatch>> {
+        if is_binary_blob(&data) {
+            return Ok(Vec::new());
         }
-
-        true
+        let label = revision.label(search_path);
+        let mut matches = pipeline.search_stored(data, search_pattern, &label).await?;
+        for m in &mut matches {
+            m.revision = Some(revision.clone());
+        }
+        Ok(matches)
     }
 
-    /// Apply safety policy constraints
-    fn apply_safety_policy(
+    /// Files to search: an explicit `--files-from` list, the git selection, or a walk of `search_paths`
+    fn source_files(
         &self,
-        safety_policy: &crate::cli::SafetyPolicy,
-        metadata: &std::fs::Metadata,
-        ext: &str,
-    ) -> bool {
-        match safety_policy {
-            crate::cli::SafetyPolicy::Conservative => {
-                let file_size = metadata.len();
-                if file_size > 10 * 1024 * 1024 {
-                    return false;
+        search_paths: &[PathBuf],
+        recursive: bool,
+        files_from: Option<&Path>,
+        null_data: bool,
+        git: &GitScope,
+        walk: &WalkOptions,
+        deadline: Option<Instant>,
+        summary: &SearchSummary,
+    ) -> RfgrepResult<Vec<std::path::PathBuf>> {
+        match files_from {
+            Some(list) => self.read_file_list(list, null_data, summary),
+            None if !git.is_empty() => {
+                let seen = SeenFiles::new(search_paths);
+                let mut files = Vec::new();
+                for root in search_paths {
+                    for file in filter_listed(root, walk, git.files(root)?)? {
+                        match file {
+                            Ok(file) if seen.first_visit(&file) => files.push(file),
+                            Ok(_) => {}
+                            Err(issue) => summary.record(&issue.path, issue.kind, issue.reason),
+                        }
+                    }
                 }
-
-                let classifier = FileTypeClassifier::new();
-                classifier.is_always_search(ext)
-            }
-            crate::cli::SafetyPolicy::Performance => {
-                let file_size = metadata.len();
-                file_size <= 500 * 1024 * 1024
+                Ok(files)
             }
-            crate::cli::SafetyPolicy::Default => true,
+            None => self.collect_files(search_paths, recursive, walk, deadline, summary),
         }
     }
 
-    /// Apply extension filters
-    fn apply_extension_filters(
+    /// Collect files from each root in turn, stopping the walk at `deadline`
+    fn collect_files(
         &self,
-        ext: &str,
-        include_extensions: &Option<Vec<String>>,
-        exclude_extensions: &Option<Vec<String>>,
-    ) -> bool {
-        // Handle include extensions
-        if let Some(include_exts) = include_extensions {
-            if !include_exts.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
-                return false;
-            }
-        }
-
-        // Handle exclude extensions
-        if let Some(exclude_exts) = exclude_extensions {
-            if exclude_exts.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
-                return false;
+        search_paths: &[PathBuf],
+        recursive: bool,
+        walk: &WalkOptions,
+        deadline: Option<Instant>,
+        summary: &SearchSummary,
+    ) -> RfgrepResult<Vec<std::path::PathBuf>> {
+        let walks = search_paths
+            .iter()
+            .map(|root| Ok((root, walk_dir_with(root, recursive, walk)?)))
+            .collect::<RfgrepResult<Vec<_>>>()?;
+        let seen = SeenFiles::new(search_paths);
+        let mut files = Vec::new();
+        'roots: for (root, entries) in walks {
+            for entry in entries {
+                if self.cancel.is_cancelled() {
+                    break 'roots;
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    summary.not_reached(root, "directory walk stopped at deadline");
+                    break 'roots;
+                }
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(issue) => {
+                        summary.record(&issue.path, issue.kind, issue.reason);
+                        continue;
+                    }
+                };
+                if entry.path().is_file() && seen.first_visit(entry.path()) {
+                    files.push(entry.path().to_path_buf());
+                }
             }
         }
-
-        true
+        Ok(files)
     }
 
-    /// Determine if file should be searched based on file type strategy
-    fn should_search_by_file_type(
+    /// Walk each of `search_paths` on `threads` threads and send the files `selection`
+    /// accepts to the returned channel as they are found
+    fn walk_parallel(
         &self,
-        path: &Path,
-        metadata: &std::fs::Metadata,
-        ext: &str,
-        search_all_files: bool,
-        text_only: bool,
-        file_types: &crate::cli::FileTypeStrategy,
-    ) -> bool {
-        if search_all_files {
-            return true;
-        }
-
-        if text_only {
-            let classifier = FileTypeClassifier::new();
-            return classifier.is_always_search(ext);
-        }
-
-        let classifier = FileTypeClassifier::new();
-        match file_types {
-            crate::cli::FileTypeStrategy::Comprehensive => !classifier.is_never_search(ext),
-            crate::cli::FileTypeStrategy::Conservative => classifier.is_always_search(ext),
-            crate::cli::FileTypeStrategy::Performance => {
-                classifier.is_always_search(ext) || classifier.is_conditional_search(ext)
-            }
-            crate::cli::FileTypeStrategy::Default => {
-                matches!(
-                    classifier.should_search(path, metadata),
-                    SearchDecision::Search(_) | SearchDecision::Conditional(_, _)
-                )
+        search_paths: &[PathBuf],
+        recursive: bool,
+        walk: &WalkOptions,
+        selection: FileSelection,
+        threads: usize,
+        deadline: Option<Instant>,
+        budget: Arc<BudgetTracker>,
+        summary: &Arc<SearchSummary>,
+    ) -> RfgrepResult<mpsc::Receiver<std::path::PathBuf>> {
+        let walkers = search_paths
+            .iter()
+            .map(|root| {
+                Ok((
+                    root.clone(),
+                    walk_dir_parallel(root, recursive, walk, threads)?,
+                ))
+            })
+            .collect::<RfgrepResult<Vec<_>>>()?;
+        let (tx, rx) = mpsc::channel(threads * 64);
+        let cancel = self.cancel.clone();
+        let summary = summary.clone();
+        let seen = SeenFiles::new(search_paths);
+        let stopped = AtomicBool::new(false);
+
+        std::thread::spawn(move || {
+            for (root, walker) in walkers {
+                if stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                walker.run(|entry| {
+                    if cancel.is_cancelled() {
+                        stopped.store(true, Ordering::Relaxed);
+                        return false;
+                    }
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        if !stopped.swap(true, Ordering::Relaxed) {
+                            if let Some(limit) = budget.time_exceeded() {
+                                summary.mark_budget_exceeded(limit);
+                            }
+                            summary.not_reached(&root, "directory walk stopped at deadline");
+                        }
+                        return false;
+                    }
+                    if let Some(limit) = budget.exceeded() {
+                        if !stopped.swap(true, Ordering::Relaxed) {
+                            summary.not_reached(
+                                &root,
+                                format!("directory walk stopped: {limit} reached"),
+                            );
+                        }
+                        return false;
+                    }
+                    match entry {
+                        Ok(entry) => {
+                            let path = entry.path();
+                            if !path.is_file()
+                                || !seen.first_visit(path)
+                                || !selection.accepts(path, &summary)
+                            {
+                                return true;
+                            }
+                            // Fails once the searchers have stopped, e.g. when stdout was closed
+                            let sent = tx.blocking_send(path.to_path_buf()).is_ok();
+                            if !sent {
+                                stopped.store(true, Ordering::Relaxed);
+                            }
+                            sent
+                        }
+                        Err(issue) => {
+                            summary.record(&issue.path, issue.kind, issue.reason);
+                            true
+                        }
+                    }
+                });
             }
-        }
+        });
+        Ok(rx)
     }
 
-    /// Apply size limits
-    fn apply_size_limits(&self, metadata: &std::fs::Metadata, max_size: Option<usize>) -> bool {
-        if let Some(max_size) = max_size {
-            let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-            if size_mb > max_size as f64 {
-                return false;
+    /// Read an explicit file list, dropping entries that are not regular files
+    fn read_file_list(
+        &self,
+        list: &Path,
+        null_separated: bool,
+        summary: &SearchSummary,
+    ) -> RfgrepResult<Vec<std::path::PathBuf>> {
+        let mut files = read_path_list(list, null_separated)?;
+        files.retain(|path| {
+            if path.is_file() {
+                true
+            } else {
+                summary.skipped(path, SkipReason::SpecialFile, "not a regular file");
+                false
             }
-        }
-        true
+        });
+        Ok(files)
     }
 
-    /// Perform the actual search
-    async fn perform_search(
+    /// Build the streaming pipeline configuration for a search
+    fn build_streaming_config(
         &self,
-        filtered_files: &[std::path::PathBuf],
-        search_pattern: &str,
         search_algorithm: SearchAlgorithm,
         context_lines: usize,
         case_sensitive: bool,
         invert_match: bool,
         max_matches: Option<usize>,
         timeout_per_file: Option<u64>,
-        threads: Option<usize>,
-    ) -> RfgrepResult<Vec<crate::processor::SearchMatch>> {
-        let config = StreamingConfig {
+        binary_strings: Option<usize>,
+    ) -> StreamingConfig {
+        StreamingConfig {
             algorithm: search_algorithm,
             context_lines,
             case_sensitive,
@@ -583,11 +1056,34 @@ impl RfgrepApp {
             timeout_per_file,
             chunk_size: 8192,
             buffer_size: 65536,
-        };
+            binary_strings,
+        }
+    }
 
-        let thread_count = threads.unwrap_or_else(|| num_cpus::get().min(8));
+    /// Create a streaming pipeline that reports into `summary` and honours `deadline`
+    fn build_pipeline(
+        &self,
+        config: StreamingConfig,
+        deadline: Option<Instant>,
+        summary: &Arc<SearchSummary>,
+    ) -> StreamingSearchPipeline {
+        let pipeline = StreamingSearchPipeline::new(config)
+            .with_summary(summary.clone())
+            .with_cancellation(self.cancel.clone());
+        match deadline {
+            Some(deadline) => pipeline.with_deadline(deadline),
+            None => pipeline,
+        }
+    }
 
-        let pipeline = StreamingSearchPipeline::new(config);
+    /// Perform the actual search, collecting every match before returning
+    async fn perform_search(
+        &self,
+        pipeline: &StreamingSearchPipeline,
+        filtered_files: &[std::path::PathBuf],
+        search_pattern: &str,
+        thread_count: usize,
+    ) -> RfgrepResult<Vec<crate::processor::SearchMatch>> {
         let file_refs: Vec<&Path> = filtered_files.iter().map(|p| p.as_path()).collect();
 
         if file_refs.len() > 10 {
@@ -597,67 +1093,186 @@ impl RfgrepApp {
         } else {
             let mut all_matches = Vec::new();
             for file in filtered_files {
-                match pipeline.search_file(file, search_pattern).await {
-                    Ok(matches) => all_matches.extend(matches),
-                    Err(e) => {
-                        eprintln!("Error searching {}: {}", file.display(), e);
-                    }
+                if self.cancel.is_cancelled() {
+                    break;
+                }
+                // Failures are recorded in the summary by the pipeline
+                if let Ok(matches) = pipeline.search_file(file, search_pattern).await {
+                    all_matches.extend(matches);
                 }
             }
             Ok(all_matches)
         }
     }
 
-    /// Output the search results
-    fn output_results(
+    /// Write each file's matches from the search workers as soon as that file finishes,
+    /// so output starts early and finished results are not held in memory
+    async fn stream_results(
+        &self,
+        mut results: mpsc::Receiver<RfgrepResult<Vec<crate::processor::SearchMatch>>>,
+        pattern: &str,
+        search_paths: &[PathBuf],
+        formatter: OutputFormatter,
+        ndjson: bool,
+        count: bool,
+        files_with_matches: bool,
+        null_data: bool,
+        summary: &SearchSummary,
+    ) -> RfgrepResult<ExitStatus> {
+        let mut header_written = false;
+
+        while let Some(result) = results.recv().await {
+            // Failures are recorded in the summary by the pipeline
+            let Ok(matches) = result else { continue };
+            summary.record_matches(&matches);
+
+            let chunk = if count {
+                continue;
+            } else if files_with_matches {
+                let terminator = if null_data { '\0' } else { '\n' };
+                format!("{}{terminator}", matches[0].path.display())
+            } else if !header_written && !ndjson {
+                header_written = true;
+                format!(
+                    "\n{}{}",
+                    formatter.format_stream_header(pattern, &display_roots(search_paths)),
+                    formatter.format_stream_batch(&matches, pattern)
+                )
+            } else {
+                formatter.format_stream_batch(&matches, pattern)
+            };
+
+            if !write_stdout(&chunk)? {
+                // The reader went away (e.g. `| head`); stop searching quietly
+                return Ok(summary.exit_status());
+            }
+        }
+
+        if self.cancel.is_cancelled() {
+            summary.mark_interrupted();
+        }
+
+        let footer = if count {
+            format!("{}\n", summary.matches())
+        } else if files_with_matches {
+            String::new()
+        } else if ndjson {
+            formatter
+                .with_summary(summary.to_json())
+                .format_stream_footer(summary.matches())
+        } else if summary.matches() == 0 {
+            format!("{}\n", "No matches found".yellow())
+        } else {
+            formatter.format_stream_footer(summary.matches())
+        };
+        write_stdout(&footer)?;
+
+        if !ndjson || count || files_with_matches {
+            eprint!("{}", summary.report());
+        }
+
+        Ok(summary.exit_status())
+    }
+
+    /// Search files for a byte pattern with the binary plugin
+    fn perform_hex_search(
+        &self,
+        plugin: &EnhancedBinarySearchPlugin,
+        files: &[std::path::PathBuf],
+        pattern: &HexPattern,
+        max_matches: Option<usize>,
+        deadline: Option<Instant>,
+        budget: &BudgetTracker,
+        summary: &SearchSummary,
+    ) -> Vec<crate::processor::SearchMatch> {
+        use rayon::prelude::*;
+
+        let mut all_matches: Vec<_> = files
+            .par_iter()
+            .flat_map_iter(|file| {
+                if self.cancel.is_cancelled() {
+                    return Vec::new();
+                }
+                let size = std::fs::metadata(file).map_or(0, |metadata| metadata.len());
+                if let Err(limit) = budget.charge(size) {
+                    summary.budget_reached(file, limit);
+                    return Vec::new();
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    summary.not_reached(file, "deadline reached before search started");
+                    return Vec::new();
+                }
+                match plugin.search_hex(file, pattern) {
+                    Ok(mut matches) => {
+                        summary.file_searched();
+                        if let Some(max_matches) = max_matches {
+                            matches.truncate(max_matches);
+                        }
+                        matches
+                    }
+                    Err(e) => {
+                        summary.error(file, &e);
+                        Vec::new()
+                    }
+                }
+            })
+            .collect();
+        all_matches.sort();
+        all_matches
+    }
+
+    /// Output the search results and the run summary, returning the exit status
+    fn output_results(
         &self,
         all_matches: &[crate::processor::SearchMatch],
         pattern: &str,
-        search_path: &Path,
+        search_paths: &[PathBuf],
         output_format: crate::cli::OutputFormat,
         ndjson: bool,
         count: bool,
         files_with_matches: bool,
+        null_data: bool,
         quiet: bool,
-    ) -> RfgrepResult<()> {
-        if all_matches.is_empty() {
-            self.output_no_matches(count, files_with_matches, output_format)
-        } else if count {
+        summary: &SearchSummary,
+    ) -> RfgrepResult<ExitStatus> {
+        // Structured formats always produce a document so the summary is never lost
+        let structured = output_format.is_structured() || ndjson;
+        summary.record_matches(all_matches);
+        if self.cancel.is_cancelled() {
+            summary.mark_interrupted();
+        }
+
+        if count {
             println!("{}", all_matches.len());
         } else if files_with_matches {
-            self.output_files_with_matches(all_matches)
+            self.output_files_with_matches(all_matches, null_data)
+        } else if all_matches.is_empty() && !structured {
+            println!("{}", "No matches found".yellow());
         } else {
             self.output_matches(
                 all_matches,
                 pattern,
-                search_path,
+                search_paths,
                 output_format,
                 ndjson,
                 quiet,
+                summary,
             )
         }
 
-        Ok(())
-    }
-
-    /// Handle case when no matches are found
-    fn output_no_matches(
-        &self,
-        count: bool,
-        files_with_matches: bool,
-        output_format: crate::cli::OutputFormat,
-    ) {
-        if count {
-            println!("0");
-        } else if files_with_matches {
-            // Print nothing
-        } else if output_format != crate::cli::OutputFormat::Json {
-            println!("{}", "No matches found".yellow());
+        if count || files_with_matches || !structured {
+            eprint!("{}", summary.report());
         }
+
+        Ok(summary.exit_status())
     }
 
     /// Output list of files containing matches
-    fn output_files_with_matches(&self, all_matches: &[crate::processor::SearchMatch]) {
+    fn output_files_with_matches(
+        &self,
+        all_matches: &[crate::processor::SearchMatch],
+        null_data: bool,
+    ) {
         use std::collections::HashSet;
         let mut unique_files: HashSet<String> = HashSet::new();
         for m in all_matches {
@@ -666,7 +1281,11 @@ impl RfgrepApp {
         let mut files: Vec<_> = unique_files.into_iter().collect();
         files.sort();
         for file in files {
-            println!("{}", file);
+            if null_data {
+                print!("{}\0", file);
+            } else {
+                println!("{}", file);
+            }
         }
     }
 
@@ -675,12 +1294,14 @@ impl RfgrepApp {
         &self,
         all_matches: &[crate::processor::SearchMatch],
         pattern: &str,
-        search_path: &Path,
+        search_paths: &[PathBuf],
         output_format: crate::cli::OutputFormat,
         ndjson: bool,
         quiet: bool,
+        summary: &SearchSummary,
     ) {
-        if !quiet && output_format != crate::cli::OutputFormat::Json && !ndjson {
+        let structured = output_format.is_structured() || ndjson;
+        if !quiet && !structured {
             println!(
                 "\n{} {} {}",
                 "Found".green(),
@@ -689,7 +1310,29 @@ impl RfgrepApp {
             );
         }
 
-        let formatter = OutputFormatter::new(if ndjson {
+        let formatter = self.build_formatter(output_format, ndjson);
+        let formatter = if structured {
+            formatter.with_summary(summary.to_json())
+        } else {
+            formatter
+        };
+
+        let output = formatter.format_results(all_matches, pattern, &display_roots(search_paths));
+
+        if structured {
+            print!("{output}");
+        } else {
+            println!("\n{output}");
+        }
+    }
+
+    /// Create the formatter for the requested output format
+    fn build_formatter(
+        &self,
+        output_format: crate::cli::OutputFormat,
+        ndjson: bool,
+    ) -> OutputFormatter {
+        OutputFormatter::new(if ndjson {
             crate::output_formats::OutputFormat::Json
         } else {
             match output_format {
@@ -700,17 +1343,11 @@ impl RfgrepApp {
                 crate::cli::OutputFormat::Markdown => crate::output_formats::OutputFormat::Markdown,
                 crate::cli::OutputFormat::Csv => crate::output_formats::OutputFormat::Csv,
                 crate::cli::OutputFormat::Tsv => crate::output_formats::OutputFormat::Tsv,
+                crate::cli::OutputFormat::Sarif => crate::output_formats::OutputFormat::Sarif,
             }
         })
-        .with_ndjson(ndjson);
-
-        let output = formatter.format_results(all_matches, pattern, search_path);
-
-        if output_format == crate::cli::OutputFormat::Json || ndjson {
-            print!("{output}");
-        } else {
-            println!("\n{output}");
-        }
+        .with_ndjson(ndjson)
+        .with_invocation(std::env::args().collect(), std::env::current_dir().ok())
     }
 
     fn handle_completions(&self, shell: clap_complete::Shell) -> RfgrepResult<()> {
@@ -720,6 +1357,202 @@ impl RfgrepApp {
         Ok(())
     }
 
+    /// Print every named file type with its globs, including `--type-add` definitions
+    fn handle_types(&self, additions: &[String]) -> RfgrepResult<()> {
+        let listing: String = type_definitions(additions)?
+            .definitions()
+            .iter()
+            .map(|definition| format!("{}: {}\n", definition.name(), definition.globs().join(", ")))
+            .collect();
+        write_stdout(&listing)?;
+        Ok(())
+    }
+
+    /// Save, run, list or remove named searches
+    async fn handle_saved(&self, command: &SavedCommands, cli: &Cli) -> RfgrepResult<ExitStatus> {
+        let dir = std::env::current_dir().map_err(RfgrepError::Io)?;
+        match command {
+            SavedCommands::Add {
+                name,
+                args,
+                description,
+                project,
+                force,
+            } => {
+                let search = SavedSearch::new(args.clone(), description.clone())?;
+                let path = if *project {
+                    dir.join(PROJECT_SAVED_FILE)
+                } else {
+                    user_saved_path()?
+                };
+                save_search(&path, name, search, *force)?;
+                println!("Saved '{}' to {}", name, path.display());
+                Ok(ExitStatus::Success)
+            }
+            SavedCommands::Run { name } => {
+                let saved = SavedSearches::load(&dir)?;
+                let search = saved.find(name)?;
+                // Global options given to `saved run` carry over to the search
+                let mut argv = vec!["rfgrep".to_string()];
+                if cli.no_config {
+                    argv.push("--no-config".to_string());
+                }
+                if let Some(profile) = &cli.profile {
+                    argv.extend(["--profile".to_string(), profile.clone()]);
+                }
+                argv.extend(search.command_line().into_iter().skip(1));
+                let search_cli = crate::config::parse_cli(argv)?;
+                Box::pin(self.run(search_cli)).await
+            }
+            SavedCommands::List => {
+                let saved = SavedSearches::load(&dir)?;
+                let mut text = String::new();
+                for (name, search, source) in saved.iter() {
+                    let scope = match source {
+                        SavedSource::User(_) => "user",
+                        SavedSource::Project(_) => "project",
+                    };
+                    text.push_str(&format!("{name} ({scope}): {}\n", search.display()));
+                    if let Some(description) = &search.description {
+                        text.push_str(&format!("    {description}\n"));
+                    }
+                }
+                if text.is_empty() {
+                    text.push_str("No saved searches\n");
+                }
+                write_stdout(&text)?;
+                Ok(ExitStatus::Success)
+            }
+            SavedCommands::Remove { name } => {
+                let saved = SavedSearches::load(&dir)?;
+                saved.find(name)?;
+                if let Some(source) = saved.source(name) {
+                    remove_search(source.path(), name)?;
+                    println!("Removed '{}' from {}", name, source.path().display());
+                }
+                Ok(ExitStatus::Success)
+            }
+        }
+    }
+
+    /// Show the merged config, write a starter file, or check each config file
+    fn handle_config(&self, command: &ConfigCommands, cli: &Cli) -> RfgrepResult<()> {
+        let dir = std::env::current_dir().map_err(RfgrepError::Io)?;
+        let sources = if cli.no_config {
+            Vec::new()
+        } else {
+            config_sources(&dir)
+        };
+        match command {
+            ConfigCommands::Show => {
+                let config = Config::load_from(&sources, cli.profile.as_deref())?;
+                let mut text = String::from("# Merged from (lowest precedence first):\n");
+                if sources.is_empty() {
+                    text.push_str("#   no config files, built-in defaults\n");
+                }
+                for path in &sources {
+                    text.push_str(&format!("#   {}\n", path.display()));
+                }
+                if let Some(profile) = &cli.profile {
+                    text.push_str(&format!("# Profile: {profile}\n"));
+                }
+                text.push('\n');
+                text.push_str(
+                    &toml::to_string_pretty(&config).map_err(|e| {
+                        RfgrepError::Other(format!("Failed to serialize config: {e}"))
+                    })?,
+                );
+                write_stdout(&text)?;
+                Ok(())
+            }
+            ConfigCommands::Init { global, force } => {
+                let path = if *global {
+                    user_config_path()?
+                } else {
+                    dir.join(PROJECT_CONFIG)
+                };
+                if path.exists() && !force {
+                    return Err(RfgrepError::Other(format!(
+                        "{} already exists; use --force to overwrite it",
+                        path.display()
+                    )));
+                }
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(RfgrepError::Io)?;
+                }
+                std::fs::write(&path, CONFIG_TEMPLATE).map_err(RfgrepError::Io)?;
+                println!("Wrote {}", path.display());
+                Ok(())
+            }
+            ConfigCommands::Validate => {
+                let mut text = String::new();
+                let mut problems = 0;
+                for path in &sources {
+                    match Config::load_from(std::slice::from_ref(path), None) {
+                        Ok(_) => text.push_str(&format!("{}: ok\n", path.display())),
+                        Err(e) => {
+                            problems += 1;
+                            text.push_str(&format!("{e}\n"));
+                        }
+                    }
+                }
+                if sources.is_empty() {
+                    text.push_str(&format!("No config files apply in {}\n", dir.display()));
+                }
+                if let (0, Some(profile)) = (problems, &cli.profile) {
+                    match Config::load_from(&sources, Some(profile)) {
+                        Ok(_) => text.push_str(&format!("profile {profile}: ok\n")),
+                        Err(e) => {
+                            problems += 1;
+                            text.push_str(&format!("{e}\n"));
+                        }
+                    }
+                }
+                write_stdout(&text)?;
+                if problems > 0 {
+                    return Err(RfgrepError::Config(format!(
+                        "{problems} config problem{} found",
+                        if problems == 1 { "" } else { "s" }
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Print the classification of each file and what decided it
+    fn handle_explain(
+        &self,
+        paths: &[PathBuf],
+        classifier: &FileTypeClassifier,
+    ) -> RfgrepResult<()> {
+        for path in paths {
+            let metadata = path
+                .metadata()
+                .map_err(|e| RfgrepError::Other(format!("Cannot read {}: {e}", path.display())))?;
+            let text = if metadata.is_file() {
+                let explanation = classifier.explain(path, &metadata);
+                let mut text = format!(
+                    "{}: {}\n  rule: {}\n  size: {} bytes\n",
+                    path.display(),
+                    explanation.decision,
+                    explanation.rule,
+                    metadata.len()
+                );
+                if let Some(size_limit) = explanation.size_limit {
+                    text.push_str(&format!("  size limit: {size_limit}\n"));
+                }
+                text
+            } else {
+                format!("{}: not a regular file\n", path.display())
+            };
+            if !write_stdout(&text)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     async fn handle_worker(&self, path: &std::path::Path, pattern: &str) -> RfgrepResult<()> {
         if let Ok(s) = std::env::var("RFGREP_WORKER_SLEEP") {
             if let Ok(sec) = s.parse::<u64>() {
@@ -767,10 +1600,17 @@ impl RfgrepApp {
         case_sensitive: bool,
         mode: &SearchMode,
         context_lines: usize,
-        _path: &str,
+        roots: &[PathBuf],
+        walk: &WalkOptions,
+        skip_binary: bool,
     ) -> RfgrepResult<()> {
         let mut terminal = init_terminal()?;
         let mut app = TuiApp::new().await?;
+        app.set_walk_options(walk.clone());
+        app.set_roots(roots.to_vec());
+        app.set_skip_binary(skip_binary);
+        let dir = std::env::current_dir().map_err(RfgrepError::Io)?;
+        app.set_saved_searches(SavedSearches::load(&dir)?);
 
         if let Some(p) = pattern {
             app.set_pattern(p.to_string());
@@ -796,16 +1636,12 @@ impl RfgrepApp {
         if let Some(p) = pattern {
             app.state.status_message = format!("Searching for: {}", p);
             let mut all_matches = Vec::new();
-            let search_root = std::path::PathBuf::from(_path);
-            let search_root = if search_root.as_os_str().is_empty() {
-                std::path::PathBuf::from(".")
-            } else {
-                search_root
-            };
-            let entries: Vec<_> = walk_dir(&search_root, true, false).collect();
+            let entries: Vec<_> = walk_roots(roots, true, walk)?
+                .filter_map(Result::ok)
+                .collect();
             for entry in entries {
                 let path = entry.path();
-                if path.is_file() {
+                if path.is_file() && !(skip_binary && crate::processor::is_binary(path)) {
                     if let Ok(mut matches) = self.plugin_manager.search_file(path, p).await {
                         all_matches.append(&mut matches);
                     }
@@ -837,13 +1673,14 @@ impl RfgrepApp {
         limit: Option<usize>,
         _copy: bool,
         _output_format: crate::cli::OutputFormat,
-        cmd_path: Option<&Path>,
-        cmd_path_flag: Option<&Path>,
-        default_path: &Path,
+        roots: &[PathBuf],
+        walk: &WalkOptions,
     ) -> RfgrepResult<()> {
-        let search_path = cmd_path_flag.or(cmd_path).unwrap_or(default_path);
-
-        let entries: Vec<_> = walk_dir(search_path, recursive, show_hidden).collect();
+        let walk = walk.or_defaults(show_hidden, !show_hidden);
+        let entries: Vec<_> = walk_roots(roots, recursive, &walk)?
+            .take_while(|_| !self.cancel.is_cancelled())
+            .filter_map(Result::ok)
+            .collect();
         let mut files: Vec<_> = entries
             .into_iter()
             .filter(|entry| entry.path().is_file())
@@ -981,6 +1818,335 @@ impl RfgrepApp {
     }
 }
 
+/// Per-file checks on size, extension, file type and safety policy, run before a file is searched
+struct FileSelection {
+    max_size: Option<usize>,
+    skip_binary: bool,
+    safety_policy: crate::cli::SafetyPolicy,
+    include_extensions: Option<Vec<String>>,
+    exclude_extensions: Option<Vec<String>>,
+    search_all_files: bool,
+    text_only: bool,
+    file_types: crate::cli::FileTypeStrategy,
+    classifier: FileTypeClassifier,
+}
+
+/// Why [`FileSelection`] rejected a file
+type Rejection = (SkipReason, String);
+
+impl FileSelection {
+    /// Keep the files that should be searched, counting the others in `summary`
+    fn filter(
+        &self,
+        files: Vec<std::path::PathBuf>,
+        summary: &SearchSummary,
+    ) -> Vec<std::path::PathBuf> {
+        files
+            .into_iter()
+            .filter(|path| self.accepts(path, summary))
+            .collect()
+    }
+
+    /// Determine if a file should be searched, counting it in `summary` when it is not
+    fn accepts(&self, path: &Path, summary: &SearchSummary) -> bool {
+        match self.check(path) {
+            Ok(()) => true,
+            Err((skip_reason, reason)) => {
+                summary.filtered(path, skip_reason, reason);
+                false
+            }
+        }
+    }
+
+    /// Why a file should not be searched, if it should not
+    fn check(&self, path: &Path) -> Result<(), Rejection> {
+        let metadata = path
+            .metadata()
+            .map_err(|e| (SkipReason::Unreadable, e.to_string()))?;
+
+        // Check binary files
+        if self.skip_binary && crate::processor::is_binary(path) {
+            return Err((SkipReason::Binary, "binary file".to_string()));
+        }
+
+        self.check_sized(path, metadata.len(), Some(&metadata))
+    }
+
+    /// Why a stored file of `size` bytes that is not on disk, such as a git blob, should
+    /// not be searched. Binary contents are only known once the file is read.
+    fn check_stored(&self, path: &Path, size: u64) -> Result<(), Rejection> {
+        self.check_sized(path, size, None)
+    }
+
+    fn check_sized(
+        &self,
+        path: &Path,
+        size: u64,
+        metadata: Option<&std::fs::Metadata>,
+    ) -> Result<(), Rejection> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|s| s.to_ascii_lowercase())
+            .unwrap_or_default();
+
+        self.apply_safety_policy(size, &ext)?;
+        self.apply_extension_filters(&ext)?;
+        self.should_search_by_file_type(path, size, metadata, &ext)?;
+        self.apply_size_limits(size)
+    }
+
+    /// Apply safety policy constraints
+    fn apply_safety_policy(&self, size: u64, ext: &str) -> Result<(), Rejection> {
+        let (limit, policy) = match self.safety_policy {
+            crate::cli::SafetyPolicy::Conservative => {
+                if !self.classifier.is_always_search(ext) {
+                    return Err((
+                        SkipReason::SafetyPolicy,
+                        format!("extension `.{ext}` is not always searched (conservative safety policy)"),
+                    ));
+                }
+                (10 * 1024 * 1024, "conservative")
+            }
+            crate::cli::SafetyPolicy::Performance => (500 * 1024 * 1024, "performance"),
+            crate::cli::SafetyPolicy::Default => return Ok(()),
+        };
+        if size > limit {
+            return Err((
+                SkipReason::SafetyPolicy,
+                format!("{size} bytes > {limit} bytes ({policy} safety policy)"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply extension filters
+    fn apply_extension_filters(&self, ext: &str) -> Result<(), Rejection> {
+        // Handle include extensions
+        if let Some(include_exts) = &self.include_extensions {
+            if !include_exts.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+                return Err((
+                    SkipReason::ExtensionFilter,
+                    "extension not in --include-extensions".to_string(),
+                ));
+            }
+        }
+
+        // Handle exclude extensions
+        if let Some(exclude_exts) = &self.exclude_extensions {
+            if exclude_exts.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+                return Err((
+                    SkipReason::ExtensionFilter,
+                    "extension in --exclude-extensions".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Determine if file should be searched based on file type strategy
+    fn should_search_by_file_type(
+        &self,
+        path: &Path,
+        size: u64,
+        metadata: Option<&std::fs::Metadata>,
+        ext: &str,
+    ) -> Result<(), Rejection> {
+        if self.search_all_files {
+            return Ok(());
+        }
+
+        let classifier = &self.classifier;
+        let (accepted, strategy) = if self.text_only {
+            (classifier.is_always_search(ext), "--text-only")
+        } else {
+            match self.file_types {
+                crate::cli::FileTypeStrategy::Comprehensive => {
+                    (!classifier.is_never_search(ext), "comprehensive file types")
+                }
+                crate::cli::FileTypeStrategy::Conservative => {
+                    (classifier.is_always_search(ext), "conservative file types")
+                }
+                crate::cli::FileTypeStrategy::Performance => (
+                    classifier.is_always_search(ext) || classifier.is_conditional_search(ext),
+                    "performance file types",
+                ),
+                crate::cli::FileTypeStrategy::Default => {
+                    let explanation = match metadata {
+                        Some(metadata) => classifier.explain(path, metadata),
+                        None => classifier.explain_stored(path, size),
+                    };
+                    return match explanation.decision {
+                        SearchDecision::Search(_) | SearchDecision::Conditional(_, _) => Ok(()),
+                        SearchDecision::Skip(reason) => Err((
+                            explanation.skip_reason.unwrap_or(SkipReason::UnknownType),
+                            reason,
+                        )),
+                    };
+                }
+            }
+        };
+        if accepted {
+            Ok(())
+        } else {
+            Err((
+                SkipReason::FileTypeStrategy,
+                format!("extension `.{ext}` not searched with {strategy}"),
+            ))
+        }
+    }
+
+    /// Apply size limits
+    fn apply_size_limits(&self, size: u64) -> Result<(), Rejection> {
+        if let Some(max_size) = self.max_size {
+            let size_mb = size as f64 / (1024.0 * 1024.0);
+            if size_mb > max_size as f64 {
+                return Err((
+                    SkipReason::SizeLimit,
+                    format!("{size} bytes > --max-size {max_size} MB"),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn load_config(cli: &Cli) -> RfgrepResult<Config> {
+    if cli.no_config {
+        Ok(Config::default())
+    } else {
+        Config::load(cli.profile.as_deref())
+    }
+}
+
+/// Walk settings from the global options on the command line and the `[ignore]` config section
+pub(crate) fn walk_options(cli: &Cli, ignore: &IgnoreConfig) -> RfgrepResult<WalkOptions> {
+    let mut filters = FileFilters {
+        modified_before: cli.changed_before,
+        owner: cli.owner.as_deref().map(resolve_owner).transpose()?,
+        perm: cli.perm,
+        executable: cli.executable,
+        ..FileFilters::default()
+    };
+    if let Some(within) = cli.changed_within {
+        filters = filters.modified_after(SystemTime::now() - within);
+    }
+    if let Some(reference) = &cli.newer {
+        filters = filters.modified_after(modified_time(reference)? + Duration::from_nanos(1));
+    }
+
+    Ok(WalkOptions {
+        globs: cli.glob.clone(),
+        iglobs: cli.iglob.clone(),
+        hidden: if cli.hidden {
+            Some(true)
+        } else if cli.no_hidden {
+            Some(false)
+        } else {
+            ignore.hidden_files.map(|skip| !skip)
+        },
+        ignore: if cli.ignore {
+            Some(true)
+        } else if cli.no_ignore {
+            Some(false)
+        } else {
+            None
+        },
+        no_ignore_vcs: cli.no_ignore_vcs,
+        no_ignore_dot: cli.no_ignore_dot,
+        no_ignore_global: cli.no_ignore_global,
+        exclude: ignore.patterns.clone(),
+        min_file_size: ignore.min_file_size,
+        follow: cli.follow,
+        stay_in_root: cli.stay_in_root,
+        max_depth: cli.max_depth,
+        one_file_system: cli.one_file_system,
+        filters,
+        types: cli.file_type.clone(),
+        types_not: cli.type_not.clone(),
+        type_add: cli.type_add.clone(),
+    })
+}
+
+/// Roots named on the command line, positional paths before `--path` ones, falling back
+/// to the global path; a root given twice is searched once
+fn search_roots(paths: &[PathBuf], path_flags: &[PathBuf], default: &Path) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for path in paths.iter().chain(path_flags) {
+        if !roots.contains(path) {
+            roots.push(path.clone());
+        }
+    }
+    if roots.is_empty() {
+        roots.push(default.to_path_buf());
+    }
+    roots
+}
+
+/// Whether the only root is `-`, standard input
+fn reads_stdin(roots: &[PathBuf]) -> bool {
+    matches!(roots, [root] if is_stdin_path(root))
+}
+
+/// Reject `-` given alongside other roots: standard input is searched on its own
+fn check_stdin_root(roots: &[PathBuf]) -> RfgrepResult<()> {
+    if roots.len() > 1 && roots.iter().any(|root| is_stdin_path(root)) {
+        return Err(RfgrepError::Other(
+            "`-` (standard input) cannot be combined with other search paths".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The roots as a single path for output headers
+fn display_roots(roots: &[PathBuf]) -> PathBuf {
+    match roots {
+        [root] => root.clone(),
+        roots => PathBuf::from(
+            roots
+                .iter()
+                .map(|root| root.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+    }
+}
+
+/// Matches in `matches` whose line text is not matched in `other` (counting repeats),
+/// i.e. the lines a commit added when comparing its version of a file with its parent's
+fn unmatched_lines(matches: &[SearchMatch], other: &[SearchMatch]) -> Vec<SearchMatch> {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for m in other {
+        *remaining.entry(m.line.as_str()).or_default() += 1;
+    }
+    matches
+        .iter()
+        .filter(|m| match remaining.get_mut(m.line.as_str()) {
+            Some(left) if *left > 0 => {
+                *left -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Write `chunk` to stdout and flush it, returning `false` if the reader has gone away
+fn write_stdout(chunk: &str) -> RfgrepResult<bool> {
+    use std::io::Write;
+    let mut stdout = std::io::stdout().lock();
+    match stdout
+        .write_all(chunk.as_bytes())
+        .and_then(|()| stdout.flush())
+    {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(false),
+        Err(e) => Err(RfgrepError::Io(e)),
+    }
+}
+
 impl Default for RfgrepApp {
     fn default() -> Self {
         Self::new().expect("Failed to create RfgrepApp")
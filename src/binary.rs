@@ -0,0 +1,396 @@
+//! Byte-level search helpers for binary files: hex patterns, xxd-style dumps and
+//! printable string extraction.
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of bytes rendered per hexdump row (matches `xxd`)
+pub const HEXDUMP_ROW_BYTES: usize = 16;
+
+/// Location and surrounding bytes of a match inside binary data
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BinaryMatch {
+    /// Absolute offset of the first matched byte
+    pub offset: u64,
+    /// The bytes that matched
+    pub bytes: Vec<u8>,
+    /// Absolute offset of the first byte in `context` (row aligned)
+    pub context_offset: u64,
+    /// Whole hexdump rows around the match
+    pub context: Vec<u8>,
+    /// Set when the match came from an extracted string rather than a byte pattern
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<StringEncoding>,
+    /// Extracted strings before a string match, with their offsets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strings_before: Vec<(u64, String)>,
+    /// Extracted strings after a string match, with their offsets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strings_after: Vec<(u64, String)>,
+}
+
+impl BinaryMatch {
+    /// Capture a match at `offset` with `context_rows` full hexdump rows on either side
+    pub fn capture(data: &[u8], offset: usize, len: usize, context_rows: usize) -> Self {
+        let row = HEXDUMP_ROW_BYTES;
+        let end = (offset + len).min(data.len());
+        let first_row = offset / row;
+        let last_row = end.saturating_sub(1).max(offset) / row;
+        let context_start = first_row.saturating_sub(context_rows) * row;
+        let context_end = ((last_row + context_rows + 1) * row).min(data.len());
+
+        Self {
+            offset: offset as u64,
+            bytes: data[offset..end].to_vec(),
+            context_offset: context_start as u64,
+            context: data[context_start..context_end].to_vec(),
+            encoding: None,
+            strings_before: Vec::new(),
+            strings_after: Vec::new(),
+        }
+    }
+
+    /// Describe the location of an extracted string
+    pub fn string(extracted: &ExtractedString) -> Self {
+        Self {
+            offset: extracted.offset,
+            bytes: extracted.encoded(),
+            context_offset: extracted.offset,
+            context: Vec::new(),
+            encoding: Some(extracted.encoding),
+            strings_before: Vec::new(),
+            strings_after: Vec::new(),
+        }
+    }
+
+    /// Render the context bytes as `xxd`-style rows
+    pub fn hexdump(&self) -> Vec<String> {
+        hexdump(self.context_offset, &self.context)
+    }
+}
+
+/// Format bytes as lowercase, space separated hex (`de ad be ef`)
+pub fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Render `data` as `xxd`-style rows starting at `base_offset`
+pub fn hexdump(base_offset: u64, data: &[u8]) -> Vec<String> {
+    data.chunks(HEXDUMP_ROW_BYTES)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut hex = String::with_capacity(40);
+            for (j, byte) in chunk.iter().enumerate() {
+                if j > 0 && j.is_multiple_of(2) {
+                    hex.push(' ');
+                }
+                hex.push_str(&format!("{byte:02x}"));
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:08x}: {:<39}  {}",
+                base_offset + (i * HEXDUMP_ROW_BYTES) as u64,
+                hex,
+                ascii
+            )
+        })
+        .collect()
+}
+
+/// Encoding of a string extracted from binary data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StringEncoding {
+    Ascii,
+    Utf16Le,
+}
+
+impl fmt::Display for StringEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StringEncoding::Ascii => write!(f, "ascii"),
+            StringEncoding::Utf16Le => write!(f, "utf-16le"),
+        }
+    }
+}
+
+/// A printable run found in binary data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedString {
+    /// Absolute offset of the first byte of the string
+    pub offset: u64,
+    pub encoding: StringEncoding,
+    pub text: String,
+}
+
+impl ExtractedString {
+    /// The string's bytes as they appear in the source data
+    pub fn encoded(&self) -> Vec<u8> {
+        match self.encoding {
+            StringEncoding::Ascii => self.text.as_bytes().to_vec(),
+            StringEncoding::Utf16Le => self.text.bytes().flat_map(|b| [b, 0]).collect(),
+        }
+    }
+}
+
+fn is_printable(byte: u8) -> bool {
+    (0x20..0x7f).contains(&byte) || byte == b'\t'
+}
+
+/// Extract printable ASCII and UTF-16LE runs of at least `min_len` characters,
+/// ordered by offset (equivalent to running `strings -a` and `strings -a -e l`)
+pub fn extract_strings(data: &[u8], min_len: usize) -> Vec<ExtractedString> {
+    let min_len = min_len.max(1);
+    let mut strings = Vec::new();
+
+    let mut start = 0;
+    for (i, &byte) in data.iter().chain(std::iter::once(&0)).enumerate() {
+        if i < data.len() && is_printable(byte) {
+            continue;
+        }
+        if i - start >= min_len {
+            strings.push(ExtractedString {
+                offset: start as u64,
+                encoding: StringEncoding::Ascii,
+                text: String::from_utf8_lossy(&data[start..i]).into_owned(),
+            });
+        }
+        start = i + 1;
+    }
+
+    let mut i = 0;
+    while i + 1 < data.len() {
+        let run_start = i;
+        let mut text = String::new();
+        while i + 1 < data.len() && is_printable(data[i]) && data[i + 1] == 0 {
+            text.push(data[i] as char);
+            i += 2;
+        }
+        if text.len() >= min_len {
+            strings.push(ExtractedString {
+                offset: run_start as u64,
+                encoding: StringEncoding::Utf16Le,
+                text,
+            });
+        } else {
+            i = run_start + 1;
+        }
+    }
+
+    strings.sort_by_key(|s| s.offset);
+    strings
+}
+
+/// A byte pattern with optional `??` wildcards, e.g. `DE AD ?? EF`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexPattern {
+    bytes: Vec<Option<u8>>,
+}
+
+impl HexPattern {
+    /// Parse a hex pattern. Whitespace is ignored, so `DEADBEEF` and `de ad be ef` are equal.
+    pub fn parse(pattern: &str) -> RfgrepResult<Self> {
+        let digits: Vec<char> = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+        if digits.is_empty() {
+            return Err(RfgrepError::Other("Empty hex pattern".to_string()));
+        }
+        if !digits.len().is_multiple_of(2) {
+            return Err(RfgrepError::Other(format!(
+                "Hex pattern '{pattern}' has an odd number of digits"
+            )));
+        }
+
+        let mut bytes = Vec::with_capacity(digits.len() / 2);
+        for pair in digits.chunks(2) {
+            let token: String = pair.iter().collect();
+            if token == "??" {
+                bytes.push(None);
+                continue;
+            }
+            let byte = u8::from_str_radix(&token, 16).map_err(|_| {
+                RfgrepError::Other(format!("Invalid byte '{token}' in hex pattern '{pattern}'"))
+            })?;
+            bytes.push(Some(byte));
+        }
+
+        if bytes.iter().all(Option::is_none) {
+            return Err(RfgrepError::Other(
+                "Hex pattern must contain at least one non-wildcard byte".to_string(),
+            ));
+        }
+
+        Ok(Self { bytes })
+    }
+
+    /// Build a pattern that matches `bytes` exactly
+    pub fn from_literal(bytes: &[u8]) -> Self {
+        Self {
+            bytes: bytes.iter().copied().map(Some).collect(),
+        }
+    }
+
+    /// Pattern length in bytes (wildcards included)
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Offsets of every (possibly overlapping) match in `haystack`
+    pub fn find_all(&self, haystack: &[u8]) -> Vec<usize> {
+        let (anchor_start, anchor) = self.longest_literal_run();
+        if anchor.is_empty() || haystack.len() < self.len() {
+            return Vec::new();
+        }
+
+        // `find_iter` skips overlapping hits, so restart one byte past each one
+        let finder = memchr::memmem::Finder::new(&anchor);
+        let mut offsets = Vec::new();
+        let mut from = 0;
+        while let Some(hit) = finder.find(&haystack[from..]).map(|hit| from + hit) {
+            from = hit + 1;
+            let Some(start) = hit.checked_sub(anchor_start) else {
+                continue;
+            };
+            if self.matches_at(haystack, start) {
+                offsets.push(start);
+            }
+        }
+        offsets
+    }
+
+    fn matches_at(&self, haystack: &[u8], start: usize) -> bool {
+        let Some(window) = haystack.get(start..start + self.len()) else {
+            return false;
+        };
+        self.bytes
+            .iter()
+            .zip(window)
+            .all(|(expected, actual)| expected.is_none_or(|b| b == *actual))
+    }
+
+    /// The longest wildcard-free run, used as the memmem anchor
+    fn longest_literal_run(&self) -> (usize, Vec<u8>) {
+        let mut best = (0, Vec::new());
+        let mut current_start = 0;
+        let mut current = Vec::new();
+        for (i, byte) in self.bytes.iter().enumerate() {
+            match byte {
+                Some(b) => {
+                    if current.is_empty() {
+                        current_start = i;
+                    }
+                    current.push(*b);
+                    if current.len() > best.1.len() {
+                        best = (current_start, current.clone());
+                    }
+                }
+                None => current.clear(),
+            }
+        }
+        best
+    }
+}
+
+impl fmt::Display for HexPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens: Vec<String> = self
+            .bytes
+            .iter()
+            .map(|b| match b {
+                Some(b) => format!("{b:02X}"),
+                None => "??".to_string(),
+            })
+            .collect();
+        write!(f, "{}", tokens.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_pattern() {
+        let spaced = HexPattern::parse("DE AD ?? EF").unwrap();
+        let packed = HexPattern::parse("dead??ef").unwrap();
+        assert_eq!(spaced, packed);
+        assert_eq!(spaced.to_string(), "DE AD ?? EF");
+
+        assert!(HexPattern::parse("DEA").is_err());
+        assert!(HexPattern::parse("ZZ").is_err());
+        assert!(HexPattern::parse("?? ??").is_err());
+    }
+
+    #[test]
+    fn test_find_with_wildcards() {
+        let data = b"\x00\xde\xad\x01\xef\xde\xad\x02\xef\xde\xad\x03\x00";
+        let pattern = HexPattern::parse("DE AD ?? EF").unwrap();
+        assert_eq!(pattern.find_all(data), vec![1, 5]);
+
+        let leading = HexPattern::parse("?? DE AD").unwrap();
+        assert_eq!(leading.find_all(data), vec![0, 4, 8]);
+    }
+
+    #[test]
+    fn test_find_overlapping_matches() {
+        let pattern = HexPattern::parse("AA AA").unwrap();
+        assert_eq!(pattern.find_all(b"\xaa\xaa\xaa"), vec![0, 1]);
+
+        let wildcard = HexPattern::parse("AA ?? AA").unwrap();
+        assert_eq!(wildcard.find_all(b"\xaa\x00\xaa\x00\xaa"), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_hexdump_matches_xxd_layout() {
+        let data: Vec<u8> = (0x41..0x41 + 18).collect();
+        let rows = hexdump(0x10, &data);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            "00000010: 4142 4344 4546 4748 494a 4b4c 4d4e 4f50  ABCDEFGHIJKLMNOP"
+        );
+        assert!(rows[1].starts_with("00000020: 5152"));
+        assert!(rows[1].ends_with("  QR"));
+    }
+
+    #[test]
+    fn test_extract_ascii_and_utf16_strings() {
+        let mut data = b"\x00\x01https://example.com\x00\x02ab\x01".to_vec();
+        data.extend_from_slice(b"v\x001\x00.\x002\x00\x00\x00");
+
+        let strings = extract_strings(&data, 4);
+        assert_eq!(strings.len(), 2);
+        assert_eq!(strings[0].offset, 2);
+        assert_eq!(strings[0].encoding, StringEncoding::Ascii);
+        assert_eq!(strings[0].text, "https://example.com");
+        assert_eq!(strings[1].offset, 26);
+        assert_eq!(strings[1].encoding, StringEncoding::Utf16Le);
+        assert_eq!(strings[1].text, "v1.2");
+        assert_eq!(strings[1].encoded(), b"v\x001\x00.\x002\x00".to_vec());
+    }
+
+    #[test]
+    fn test_capture_context_rows() {
+        let data = vec![0u8; 64];
+        let m = BinaryMatch::capture(&data, 36, 4, 1);
+        assert_eq!(m.offset, 36);
+        assert_eq!(m.context_offset, 16);
+        assert_eq!(m.context.len(), 48);
+    }
+}
@@ -0,0 +1,276 @@
+//! Hard resource limits for a search run.
+//!
+//! Each [`SafetyPolicy`] carries a default [`ResourceBudget`]: the default policy
+//! is unlimited, `performance` only caps resident memory at
+//! `performance.max_memory_usage_mb`, and `conservative` also caps the bytes read,
+//! the number of files searched and the wall time. `--max-bytes`, `--max-files`,
+//! `--max-memory` and `--max-time` override the policy's values.
+//!
+//! A [`BudgetTracker`] charges every file against the budget before it is
+//! searched. Once any limit is reached no further files are started, the run
+//! stops with partial results, and the summary names the limit that ended it.
+//! Resident memory is sampled rather than read on every charge: every
+//! [`MEMORY_SAMPLE_FILES`] files, or sooner once [`MEMORY_SAMPLE_INTERVAL`] has passed.
+use crate::cli::SafetyPolicy;
+use crate::config::PerformanceConfig;
+use crate::memory::{resident_memory, AdaptiveMemoryManager};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Bytes the conservative policy reads before stopping
+pub const CONSERVATIVE_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+/// Files the conservative policy searches before stopping
+pub const CONSERVATIVE_MAX_FILES: usize = 100_000;
+/// Wall time the conservative policy runs before stopping
+pub const CONSERVATIVE_MAX_TIME: Duration = Duration::from_secs(300);
+/// Files charged between resident memory samples
+pub const MEMORY_SAMPLE_FILES: usize = 64;
+/// Longest wait between resident memory samples while files are being charged
+pub const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The limit of a [`ResourceBudget`] that stopped a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetLimit {
+    /// Total bytes of the files searched
+    Bytes(u64),
+    /// Number of files searched
+    Files(usize),
+    /// Resident memory, in megabytes
+    Memory(u64),
+    /// Wall time since the run started
+    Time(Duration),
+}
+
+impl BudgetLimit {
+    /// Name of the limit as used in JSON output
+    pub fn name(&self) -> &'static str {
+        match self {
+            BudgetLimit::Bytes(_) => "max_bytes",
+            BudgetLimit::Files(_) => "max_files",
+            BudgetLimit::Memory(_) => "max_memory",
+            BudgetLimit::Time(_) => "max_time",
+        }
+    }
+}
+
+impl fmt::Display for BudgetLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetLimit::Bytes(bytes) => write!(f, "read limit of {bytes} bytes"),
+            BudgetLimit::Files(files) => write!(f, "limit of {files} files"),
+            BudgetLimit::Memory(mb) => write!(f, "memory limit of {mb} MB"),
+            BudgetLimit::Time(time) => write!(f, "time limit of {time:?}"),
+        }
+    }
+}
+
+/// Hard limits for one search run; `None` leaves a resource unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceBudget {
+    pub max_bytes: Option<u64>,
+    pub max_files: Option<usize>,
+    pub max_memory_mb: Option<u64>,
+    pub max_time: Option<Duration>,
+}
+
+impl ResourceBudget {
+    /// Default limits of `policy`
+    pub fn for_policy(policy: &SafetyPolicy, performance: &PerformanceConfig) -> Self {
+        match policy {
+            SafetyPolicy::Default => Self::default(),
+            SafetyPolicy::Conservative => Self {
+                max_bytes: Some(CONSERVATIVE_MAX_BYTES),
+                max_files: Some(CONSERVATIVE_MAX_FILES),
+                max_memory_mb: Some(performance.max_memory_usage_mb),
+                max_time: Some(CONSERVATIVE_MAX_TIME),
+            },
+            SafetyPolicy::Performance => Self {
+                max_memory_mb: Some(performance.max_memory_usage_mb),
+                ..Self::default()
+            },
+        }
+    }
+
+    /// Fill the limits left unset from `defaults`
+    pub fn or(self, defaults: Self) -> Self {
+        Self {
+            max_bytes: self.max_bytes.or(defaults.max_bytes),
+            max_files: self.max_files.or(defaults.max_files),
+            max_memory_mb: self.max_memory_mb.or(defaults.max_memory_mb),
+            max_time: self.max_time.or(defaults.max_time),
+        }
+    }
+}
+
+/// Thread-safe accounting of a run against its [`ResourceBudget`]
+pub struct BudgetTracker {
+    budget: ResourceBudget,
+    memory: AdaptiveMemoryManager,
+    started: Instant,
+    bytes: AtomicU64,
+    files: AtomicUsize,
+    /// Charges made, for spacing out memory samples
+    charges: AtomicUsize,
+    /// Milliseconds after `started` of the last memory sample
+    last_sample: AtomicU64,
+    exceeded: OnceLock<BudgetLimit>,
+}
+
+impl BudgetTracker {
+    /// Start accounting now; resident memory samples go to an
+    /// [`AdaptiveMemoryManager`] for `performance`
+    pub fn new(budget: ResourceBudget, performance: &PerformanceConfig) -> Self {
+        let mut performance = performance.clone();
+        if let Some(max_memory_mb) = budget.max_memory_mb {
+            performance.max_memory_usage_mb = max_memory_mb;
+        }
+        Self {
+            budget,
+            memory: AdaptiveMemoryManager::new(performance),
+            started: Instant::now(),
+            bytes: AtomicU64::new(0),
+            files: AtomicUsize::new(0),
+            charges: AtomicUsize::new(0),
+            last_sample: AtomicU64::new(0),
+            exceeded: OnceLock::new(),
+        }
+    }
+
+    pub fn budget(&self) -> &ResourceBudget {
+        &self.budget
+    }
+
+    /// Charge one file of `size` bytes before searching it, or return the limit
+    /// that stops the run. Once a limit is reached every later charge fails.
+    ///
+    /// Files on disk are charged their metadata size up front, so a file the search
+    /// stops reading early (deadline, `--max-count`) still counts in full, and one
+    /// that grows while it is read counts at its earlier size.
+    pub fn charge(&self, size: u64) -> Result<(), BudgetLimit> {
+        if let Some(limit) = self.exceeded() {
+            return Err(limit);
+        }
+        self.time_exceeded().map_or(Ok(()), Err)?;
+        if let Some(max_files) = self.budget.max_files {
+            if self.files.fetch_add(1, Ordering::Relaxed) >= max_files {
+                return Err(self.exceed(BudgetLimit::Files(max_files)));
+            }
+        }
+        if let Some(max_bytes) = self.budget.max_bytes {
+            if self.bytes.fetch_add(size, Ordering::Relaxed) + size > max_bytes {
+                return Err(self.exceed(BudgetLimit::Bytes(max_bytes)));
+            }
+        }
+        if let Some(max_memory_mb) = self.budget.max_memory_mb {
+            if let Some(resident) = self.sample_due().then(resident_memory).flatten() {
+                self.memory.record_resident_memory(resident);
+                if self.memory.exceeds_limit(resident) {
+                    return Err(self.exceed(BudgetLimit::Memory(max_memory_mb)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether this charge should sample resident memory
+    fn sample_due(&self) -> bool {
+        let charge = self.charges.fetch_add(1, Ordering::Relaxed);
+        let now = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        let since = now.saturating_sub(self.last_sample.load(Ordering::Relaxed));
+        if !charge.is_multiple_of(MEMORY_SAMPLE_FILES)
+            && u128::from(since) < MEMORY_SAMPLE_INTERVAL.as_millis()
+        {
+            return false;
+        }
+        self.last_sample.store(now, Ordering::Relaxed);
+        true
+    }
+
+    /// The time limit, once the run has used it up
+    pub fn time_exceeded(&self) -> Option<BudgetLimit> {
+        let max_time = self.budget.max_time?;
+        (self.started.elapsed() >= max_time).then(|| self.exceed(BudgetLimit::Time(max_time)))
+    }
+
+    /// The first limit the run reached, if any
+    pub fn exceeded(&self) -> Option<BudgetLimit> {
+        self.exceeded.get().copied()
+    }
+
+    fn exceed(&self, limit: BudgetLimit) -> BudgetLimit {
+        *self.exceeded.get_or_init(|| limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_defaults_and_overrides() {
+        let performance = PerformanceConfig::default();
+        assert_eq!(
+            ResourceBudget::for_policy(&SafetyPolicy::Default, &performance),
+            ResourceBudget::default()
+        );
+        let conservative = ResourceBudget::for_policy(&SafetyPolicy::Conservative, &performance);
+        assert_eq!(conservative.max_files, Some(CONSERVATIVE_MAX_FILES));
+        assert_eq!(
+            conservative.max_memory_mb,
+            Some(performance.max_memory_usage_mb)
+        );
+
+        let budget = ResourceBudget {
+            max_files: Some(10),
+            ..ResourceBudget::default()
+        }
+        .or(conservative);
+        assert_eq!(budget.max_files, Some(10));
+        assert_eq!(budget.max_bytes, Some(CONSERVATIVE_MAX_BYTES));
+    }
+
+    #[test]
+    fn test_tracker_stops_at_first_limit() {
+        let budget = ResourceBudget {
+            max_bytes: Some(100),
+            max_files: Some(3),
+            ..ResourceBudget::default()
+        };
+        let tracker = BudgetTracker::new(budget, &PerformanceConfig::default());
+        assert!(tracker.charge(40).is_ok());
+        assert!(tracker.charge(40).is_ok());
+        assert_eq!(tracker.charge(40), Err(BudgetLimit::Bytes(100)));
+        // The first limit reached sticks, even though the file limit is hit next
+        assert_eq!(tracker.charge(0), Err(BudgetLimit::Bytes(100)));
+        assert_eq!(tracker.exceeded(), Some(BudgetLimit::Bytes(100)));
+    }
+
+    #[test]
+    fn test_memory_is_sampled_every_few_files() {
+        let tracker = BudgetTracker::new(ResourceBudget::default(), &PerformanceConfig::default());
+        assert!(tracker.sample_due());
+        let sampled = (1..MEMORY_SAMPLE_FILES)
+            .filter(|_| tracker.sample_due())
+            .count();
+        // Only the timer can trigger a sample between the file-count ones
+        assert!(sampled <= 1);
+        assert!(tracker.sample_due());
+    }
+
+    #[test]
+    fn test_file_limit() {
+        let budget = ResourceBudget {
+            max_files: Some(1),
+            ..ResourceBudget::default()
+        };
+        let tracker = BudgetTracker::new(budget, &PerformanceConfig::default());
+        assert!(tracker.charge(1 << 40).is_ok());
+        assert_eq!(tracker.charge(0), Err(BudgetLimit::Files(1)));
+        assert_eq!(
+            tracker.exceeded().map(|limit| limit.name()),
+            Some("max_files")
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Cooperative cancellation shared between the Ctrl-C handler and search workers.
+//!
+//! The token is checked between files while walking and searching, and every few
+//! hundred lines inside a file, so an interrupted run stops promptly but still
+//! writes well-formed output for what it already found.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Cloneable flag that requests a running search to stop
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation; every clone of the token observes it
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_is_shared_between_clones() {
+        let token = CancellationToken::new();
+        let worker = token.clone();
+        assert!(!worker.is_cancelled());
+
+        token.cancel();
+        assert!(worker.is_cancelled());
+    }
+}
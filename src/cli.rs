@@ -1,8 +1,10 @@
+use crate::file_filters::PermFilter;
 use clap::{Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 #[derive(Parser)]
 #[clap(
@@ -87,6 +89,121 @@ pub struct Cli {
     #[clap(long, value_parser, global = true)]
     pub threads: Option<usize>,
 
+    /// Include or exclude paths matching a glob (e.g. 'src/**/*.rs', '!**/generated/**'); repeatable
+    #[clap(long, value_name = "GLOB", global = true)]
+    pub glob: Vec<String>,
+
+    /// Like --glob, but matched case-insensitively; repeatable
+    #[clap(long, value_name = "GLOB", global = true)]
+    pub iglob: Vec<String>,
+
+    /// Include hidden files and directories (search and interactive include them by default)
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub hidden: bool,
+
+    /// Skip hidden files and directories
+    #[clap(
+        long,
+        value_parser,
+        default_value_t = false,
+        global = true,
+        conflicts_with = "hidden"
+    )]
+    pub no_hidden: bool,
+
+    /// Respect .gitignore, .ignore, .rfgrepignore and the global gitignore (list and tui
+    /// respect them by default)
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub ignore: bool,
+
+    /// Don't respect .gitignore, .ignore, .rfgrepignore or the global gitignore
+    #[clap(
+        long,
+        value_parser,
+        default_value_t = false,
+        global = true,
+        conflicts_with = "ignore"
+    )]
+    pub no_ignore: bool,
+
+    /// Don't respect .gitignore and .git/info/exclude
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub no_ignore_vcs: bool,
+
+    /// Don't respect .ignore and .rfgrepignore files
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub no_ignore_dot: bool,
+
+    /// Don't respect the global gitignore (core.excludesFile)
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub no_ignore_global: bool,
+
+    /// Follow symbolic links; link loops and broken links are reported in the summary
+    #[clap(
+        long,
+        short = 'L',
+        value_parser,
+        default_value_t = false,
+        global = true
+    )]
+    pub follow: bool,
+
+    /// With --follow, don't follow links that resolve outside the search root
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub stay_in_root: bool,
+
+    /// Walk at most N levels below the search root (1 = its direct entries); implies recursion
+    #[clap(long, value_name = "N", global = true)]
+    pub max_depth: Option<usize>,
+
+    /// Don't descend into directories on a different filesystem from the search root
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub one_file_system: bool,
+
+    /// Only files modified within DURATION of now (e.g. 1h, 2d)
+    #[clap(long, value_parser = parse_duration, value_name = "DURATION", global = true)]
+    pub changed_within: Option<Duration>,
+
+    /// Only files modified before DATE (YYYY-MM-DD, YYYY-MM-DD HH:MM:SS, RFC 3339) or DURATION ago
+    #[clap(long, value_parser = parse_time, value_name = "DATE", global = true)]
+    pub changed_before: Option<SystemTime>,
+
+    /// Only files modified more recently than FILE
+    #[clap(long, value_name = "FILE", global = true)]
+    pub newer: Option<PathBuf>,
+
+    /// Only files owned by USER (name or uid)
+    #[clap(long, value_name = "USER", global = true)]
+    pub owner: Option<String>,
+
+    /// Only files whose permissions match MODE, as in find -perm (e.g. -o+w, 644, -022)
+    #[clap(long, value_parser = PermFilter::parse, value_name = "MODE", allow_hyphen_values = true, global = true)]
+    pub perm: Option<PermFilter>,
+
+    /// Only executable files
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub executable: bool,
+
+    /// Only files of type NAME (see `rfgrep types`); repeatable
+    #[clap(long = "type", short = 't', value_name = "NAME", global = true)]
+    pub file_type: Vec<String>,
+
+    /// Skip files of type NAME; repeatable
+    #[clap(long, short = 'T', value_name = "NAME", global = true)]
+    pub type_not: Vec<String>,
+
+    /// Define or extend a file type, as NAME:GLOB (e.g. proto:*.proto); repeatable
+    #[clap(long, value_name = "NAME:GLOB", global = true)]
+    pub type_add: Vec<String>,
+
+    /// Ignore config files and use the built-in defaults
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub no_config: bool,
+
+    /// Apply the [profiles.NAME] settings from the config files
+    #[clap(long, value_name = "NAME", global = true, conflicts_with = "no_config")]
+    pub profile: Option<String>,
+
     #[clap(subcommand)]
     pub command: Commands,
 }
@@ -98,6 +215,8 @@ pub enum ColorChoice {
     Never,
 }
 
+// Parsed once per run, so the size of the search variant does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Run simulations and performance benchmarks to evaluate the current implementation
@@ -132,6 +251,33 @@ EXAMPLES:
   # Search and copy results to clipboard
   rfgrep search "TODO" --copy --extensions rs,md
 
+  # Search several directories at once
+  rfgrep search "TODO" --recursive -- src tests benches
+
+  # Search standard input
+  cat build.log | rfgrep search "error" -- -
+
+  # Search an explicit, NUL-separated file list
+  git ls-files -z | rfgrep search "TODO" --files-from - -0
+
+  # Find a byte sequence with wildcards in firmware images
+  rfgrep search --hex "DE AD ?? EF" --context-lines 2 -- firmware.bin
+
+  # Find embedded URLs in build artifacts
+  rfgrep search "https?://" --mode regex --binary-strings --recursive -- target/release
+
+  # Deterministic output for diffing between runs
+  rfgrep search "TODO" --recursive --sort path -- .
+
+  # Find out why an expected file was never searched
+  rfgrep search "TODO" --recursive --show-skipped -- .
+
+  # Give up after 30 seconds and report what was found so far
+  rfgrep search "TODO" --recursive --deadline 30s -- .
+
+  # Stop cleanly after reading 2 GiB or searching 10000 files
+  rfgrep search "TODO" --recursive --max-bytes 2G --max-files 10000 -- /srv
+
 PERFORMANCE TIPS:
   • Use --skip-binary for faster processing
   • Limit file size with --max-size
@@ -143,6 +289,18 @@ PERFORMANCE TIPS:
         #[clap(long, value_enum, default_value_t = SearchMode::Text)]
         mode: SearchMode,
 
+        /// Treat PATTERN as hex bytes with `??` wildcards (e.g. "DE AD ?? EF") and report byte offsets
+        #[clap(long, value_parser, default_value_t = false)]
+        hex: bool,
+
+        /// Search printable ASCII and UTF-16LE strings extracted from binaries (like `strings -a -e l`)
+        #[clap(long, value_parser, default_value_t = false, conflicts_with = "hex")]
+        binary_strings: bool,
+
+        /// Minimum length of strings extracted in --binary-strings mode
+        #[clap(long, value_parser, default_value_t = 4)]
+        min_string_len: usize,
+
         #[clap(long, value_parser, default_value_t = false)]
         copy: bool,
 
@@ -179,6 +337,15 @@ PERFORMANCE TIPS:
         #[clap(short, long, value_parser, default_value_t = false)]
         recursive: bool,
 
+        /// Don't recurse, even when the config sets `recursive = true`
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with = "recursive"
+        )]
+        no_recursive: bool,
+
         #[clap(long, value_parser, default_value_t = 0)]
         context_lines: usize,
 
@@ -192,6 +359,30 @@ PERFORMANCE TIPS:
         #[clap(long, value_parser)]
         timeout_per_file: Option<u64>,
 
+        /// Sort results instead of streaming them as each file finishes
+        #[clap(long, value_enum, value_name = "ORDER")]
+        sort: Option<MatchSort>,
+
+        /// Stop the whole search after DURATION (e.g. 30s, 500ms, 2m) and report partial results
+        #[clap(long, value_parser = parse_duration, value_name = "DURATION")]
+        deadline: Option<Duration>,
+
+        /// Stop after reading SIZE bytes of files (e.g. 500M, 2G); overrides the safety policy
+        #[clap(long, value_parser = parse_size, value_name = "SIZE")]
+        max_bytes: Option<u64>,
+
+        /// Stop after searching N files; overrides the safety policy
+        #[clap(long, value_parser, value_name = "N")]
+        max_files: Option<usize>,
+
+        /// Stop once resident memory exceeds MB megabytes; overrides the safety policy
+        #[clap(long, value_parser, value_name = "MB")]
+        max_memory: Option<u64>,
+
+        /// Stop after DURATION of wall time (e.g. 30s, 5m); overrides the safety policy
+        #[clap(long, value_parser = parse_duration, value_name = "DURATION")]
+        max_time: Option<Duration>,
+
         #[clap(long, value_parser)]
         max_matches: Option<usize>,
 
@@ -206,12 +397,83 @@ PERFORMANCE TIPS:
         #[clap(long, short = 'l', value_parser, default_value_t = false)]
         files_with_matches: bool,
 
+        /// List every file that was not searched and why (type, size limit, binary, ...)
+        #[clap(long, value_parser, default_value_t = false)]
+        show_skipped: bool,
+
+        /// Paths to search, after `--`, each walked with the same filters; use `-` to search
+        /// standard input
         #[clap(value_parser, last = true)]
-        path: Option<PathBuf>,
+        path: Vec<PathBuf>,
 
-        /// Alternative explicit path flag (useful for scripts)
+        /// Alternative explicit path flag (useful for scripts); repeatable
         #[clap(long, value_parser, alias = "path-flag")]
-        path_flag: Option<PathBuf>,
+        path_flag: Vec<PathBuf>,
+
+        /// Read the list of files to search from FILE (`-` for standard input)
+        #[clap(long, value_parser, value_name = "FILE", conflicts_with = "path")]
+        files_from: Option<PathBuf>,
+
+        /// Use NUL instead of newline to separate paths in --files-from input and -l output
+        #[clap(short = '0', long = "null", value_parser, default_value_t = false)]
+        null_data: bool,
+
+        /// Search only files changed relative to REF (default HEAD), staged or not; give the
+        /// ref as `--git-changed=REF`
+        #[clap(
+            long,
+            value_name = "REF",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "HEAD",
+            conflicts_with = "files_from"
+        )]
+        git_changed: Option<String>,
+
+        /// Search only files with staged changes
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with = "files_from"
+        )]
+        git_staged: bool,
+
+        /// Search only untracked files that are not ignored
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with = "files_from"
+        )]
+        git_untracked: bool,
+
+        /// Search only files tracked by git; with the other git options, only the tracked
+        /// files among theirs
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with_all = ["files_from", "git_untracked"]
+        )]
+        git_tracked_only: bool,
+
+        /// Search the tree of commit REV (branch, tag or commit id) without checking it out
+        #[clap(
+            long,
+            value_name = "REV",
+            conflicts_with_all = ["files_from", "git_changed", "git_staged", "git_untracked", "git_tracked_only", "hex"]
+        )]
+        rev: Option<String>,
+
+        /// Search history for commits that added or removed matching lines (from --rev or HEAD)
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with_all = ["files_from", "git_changed", "git_staged", "git_untracked", "git_tracked_only", "hex"]
+        )]
+        history: bool,
     },
 
     #[clap(after_help = r#"
@@ -273,6 +535,9 @@ EXAMPLES:
   # List files under 1MB
   rfgrep list --max-size 1 --extensions rs
 
+  # List the Rust files of several directories
+  rfgrep list --recursive --extensions rs -- src tests
+
 FEATURES:
   • Extension statistics and file counts
   • Binary file detection
@@ -323,13 +588,13 @@ FEATURES:
         #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
         output_format: OutputFormat,
 
-        // Optional trailing path allowing `rfgrep list <options> <path>`
+        // Optional trailing paths allowing `rfgrep list <options> -- <path>...`
         #[clap(value_parser, last = true)]
-        path: Option<PathBuf>,
+        path: Vec<PathBuf>,
 
-        /// Alternative explicit path flag (useful for scripts)
+        /// Alternative explicit path flag (useful for scripts); repeatable
         #[clap(long, value_parser, alias = "path-flag")]
-        path_flag: Option<PathBuf>,
+        path_flag: Vec<PathBuf>,
     },
     #[clap(after_help = r#"
 SUPPORTED SHELLS:
@@ -433,9 +698,81 @@ CONTROLS:
         /// Number of context lines to show
         #[clap(long, default_value = "0")]
         context_lines: usize,
-        /// Search path
+        /// Search path; repeatable
         #[clap(long, default_value = ".")]
-        path: String,
+        path: Vec<PathBuf>,
+    },
+    /// List the named file types usable with --type and --type-not
+    #[clap(after_help = r#"
+EXAMPLES:
+  # Show every type and its globs
+  rfgrep types
+
+  # Include a custom type in the listing
+  rfgrep types --type-add 'proto:*.proto'
+
+  # Search only Rust and TOML files
+  rfgrep search "serde" --type rust --type toml --recursive
+"#)]
+    Types {},
+    /// Show how each file is classified: whether it is searched, and the rule, size
+    /// limit or MIME sniff that decided it
+    #[clap(after_help = r#"
+EXAMPLES:
+  # Why is this file not searched?
+  rfgrep explain assets/logo.svg
+
+  # Check several files at once
+  rfgrep explain Cargo.lock scripts/deploy target/app.bin
+
+Rules can be changed in the [file_types] section of the config file.
+"#)]
+    Explain {
+        /// Files to classify
+        #[clap(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Save, list and run named searches
+    #[clap(after_help = r#"
+Saved searches live in saved.toml next to the user config, and in
+.rfgrep-saved.toml files that can be committed to share them with a project.
+In the TUI, enter @NAME at the search prompt to run a saved search's pattern.
+
+EXAMPLES:
+  # Save an audit for everyone working on the project
+  rfgrep saved add todo-owners --project -- 'TODO\((\w+)\)' --mode regex --algorithm regex --recursive -- src
+
+  # Run it
+  rfgrep saved run todo-owners
+
+  # Show every saved search and where it is defined
+  rfgrep saved list
+"#)]
+    Saved {
+        #[clap(subcommand)]
+        command: SavedCommands,
+    },
+    /// Show, create or check the configuration files
+    #[clap(after_help = r#"
+CONFIG FILES (lowest precedence first):
+  $XDG_CONFIG_HOME/rfgrep/config.toml, then ~/.rfgrep.toml
+  every .rfgrep.toml from the filesystem root down to the current directory
+  [profiles.NAME] from the merged files, with --profile NAME
+  flags on the command line always win
+
+EXAMPLES:
+  # Print the merged configuration and the files it came from
+  rfgrep config show --profile ci
+
+  # Start a project config in the current directory
+  rfgrep config init
+
+  # Check every config file that applies here
+  rfgrep config validate
+"#)]
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommands,
     },
     #[clap(hide = true)]
     Worker {
@@ -444,6 +781,56 @@ CONTROLS:
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum SavedCommands {
+    /// Save the arguments of an `rfgrep search` command under NAME
+    Add {
+        /// Name of the saved search
+        name: String,
+        /// Arguments following `rfgrep search`, after `--`
+        #[clap(last = true, required = true, value_name = "SEARCH ARGS")]
+        args: Vec<String>,
+        /// Short description shown by `saved list`
+        #[clap(long)]
+        description: Option<String>,
+        /// Save to ./.rfgrep-saved.toml instead of the user file
+        #[clap(long)]
+        project: bool,
+        /// Replace a saved search of the same name
+        #[clap(long)]
+        force: bool,
+    },
+    /// Run a saved search
+    Run {
+        /// Name of the saved search
+        name: String,
+    },
+    /// List the saved searches and where each is defined
+    List,
+    /// Remove a saved search from the file that defines it
+    Remove {
+        /// Name of the saved search
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print the effective configuration merged from every config file
+    Show,
+    /// Write a starter config file
+    Init {
+        /// Write the user config instead of ./.rfgrep.toml
+        #[clap(long)]
+        global: bool,
+        /// Overwrite an existing file
+        #[clap(long)]
+        force: bool,
+    },
+    /// Check each config file that applies in the current directory
+    Validate,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum PluginCommands {
     /// List all available plugins
@@ -489,6 +876,7 @@ pub enum PluginCommands {
 }
 
 #[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum SearchMode {
     #[default]
     Text,
@@ -510,13 +898,15 @@ pub enum FileTypeStrategy {
 }
 
 #[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum SafetyPolicy {
     #[default]
-    /// Default safety policy - balanced approach
+    /// Default safety policy - balanced approach, no resource budget
     Default,
-    /// Conservative safety - strict file type checking and size limits
+    /// Conservative safety - strict file type checking and size limits, and a budget
+    /// of 1 GiB read, 100000 files, `max_memory_usage_mb` of memory and 5 minutes
     Conservative,
-    /// Performance mode - relaxed safety for speed
+    /// Performance mode - relaxed safety for speed, memory capped at `max_memory_usage_mb`
     Performance,
 }
 
@@ -544,7 +934,15 @@ pub enum SortCriteria {
     Path,
 }
 
+/// Result ordering for `search --sort`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchSort {
+    /// By file path, then line number
+    Path,
+}
+
 #[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputFormat {
     #[default]
     Text,
@@ -554,6 +952,15 @@ pub enum OutputFormat {
     Markdown,
     Csv,
     Tsv,
+    Sarif,
+}
+
+impl OutputFormat {
+    /// Whether the format is a single machine-readable document that carries the
+    /// run summary, so results are written even when there are none
+    pub fn is_structured(&self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Sarif)
+    }
 }
 
 impl fmt::Display for SearchMode {
@@ -565,3 +972,82 @@ impl fmt::Display for SearchMode {
         }
     }
 }
+
+/// Parse a size such as `4096`, `64K`, `500M` or `2G` (powers of 1024) into bytes
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid size '{value}'"))?;
+    let shift = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" | "KIB" => 10,
+        "M" | "MB" | "MIB" => 20,
+        "G" | "GB" | "GIB" => 30,
+        "T" | "TB" | "TIB" => 40,
+        other => return Err(format!("unknown size unit '{other}' (use K, M, G or T)")),
+    };
+    amount
+        .checked_mul(1 << shift)
+        .ok_or_else(|| format!("size '{value}' is too large"))
+}
+
+/// Parse a duration such as `500ms`, `30s`, `2m`, `1h` or `2d`; a bare number is seconds
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration '{value}'"))?;
+    let seconds = match unit.trim() {
+        "ms" => amount / 1000.0,
+        "" | "s" => amount,
+        "m" => amount * 60.0,
+        "h" => amount * 3600.0,
+        "d" => amount * 86400.0,
+        other => {
+            return Err(format!(
+                "unknown duration unit '{other}' (use ms, s, m, h or d)"
+            ))
+        }
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("invalid duration '{value}'"))
+}
+
+/// Parse a point in time: a date (`2024-05-01`), a local date and time
+/// (`2024-05-01 13:30:00` or with a `T`), an RFC 3339 timestamp, or a duration
+/// before now as accepted by [`parse_duration`]
+pub fn parse_time(value: &str) -> Result<SystemTime, String> {
+    use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+
+    let value = value.trim();
+    if let Ok(ago) = parse_duration(value) {
+        return SystemTime::now()
+            .checked_sub(ago)
+            .ok_or_else(|| format!("duration '{value}' is too large"));
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.into());
+    }
+    let local = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| format!("invalid date '{value}' (use YYYY-MM-DD or YYYY-MM-DD HH:MM:SS)"))?;
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(SystemTime::from)
+        .ok_or_else(|| format!("invalid local time '{value}'"))
+}
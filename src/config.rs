@@ -1,12 +1,55 @@
-use crate::cli::SearchMode;
+//! Configuration files and their merge with the command line.
+//!
+//! Settings cascade from the user config (`$XDG_CONFIG_HOME/rfgrep/config.toml`,
+//! then `~/.rfgrep.toml`) down through every `.rfgrep.toml` from the filesystem
+//! root to the current directory. Tables merge key by key and a closer file
+//! replaces any other value it sets, arrays included. `--profile NAME` then
+//! overlays the `[profiles.NAME]` table, and flags given on the command line
+//! always win over all of them.
+use crate::cli::{Cli, Commands, OutputFormat, SafetyPolicy, SearchMode};
 use crate::error::{Result as RfgrepResult, RfgrepError};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(default)]
+/// Per-directory config file name
+pub const PROJECT_CONFIG: &str = ".rfgrep.toml";
+
+/// Starter config written by `rfgrep config init`
+pub const CONFIG_TEMPLATE: &str = r#"# rfgrep configuration
+#
+# Settings cascade from the user config down through every .rfgrep.toml from the
+# filesystem root to the current directory; closer files win, and flags on the
+# command line always win. `rfgrep config show` prints the merged result.
+
+[search]
+# default_mode = "text"            # --mode: text, word or regex
+# context_lines = 2                # --context-lines
+# recursive = true                 # --recursive
+# output_format = "text"           # --output-format
+# default_extensions = ["rs"]      # --include-extensions
+# max_file_size = 100              # --max-size, in MB
+# parallel_jobs = 8                # --threads
+# safety_policy = "default"        # --safety-policy
+
+[ignore]
+patterns = ["node_modules", ".git"]
+# binary_files = true             # skip binary files, like --skip-binary
+# hidden_files = true             # skip dotfiles unless --hidden is given
+
+# Select a profile with --profile ci; it uses the same sections as above
+# [profiles.ci.search]
+# output_format = "json"
+# safety_policy = "conservative"
+"#;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
     #[serde(default)]
     pub search: SearchConfig,
@@ -19,19 +62,42 @@ pub struct Config {
 
     #[serde(default)]
     pub performance: PerformanceConfig,
+
+    #[serde(default)]
+    pub file_types: FileTypesConfig,
+
+    /// Named overlays selected with `--profile`, each using the sections above
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, toml::Table>,
 }
 
+/// Defaults for flags, from the `[search]` section. A flag given on the command
+/// line always wins.
 #[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SearchConfig {
-    pub default_mode: SearchMode,
-    pub context_lines: usize,
-    pub max_file_size: Option<u64>,
-    pub chunk_size: usize,
+    /// `search --mode`
+    pub default_mode: Option<SearchMode>,
+    /// `search --context-lines`
+    pub context_lines: Option<usize>,
+    /// `search --recursive`
+    pub recursive: Option<bool>,
+    /// `search --output-format`
+    pub output_format: Option<OutputFormat>,
+    /// `search --include-extensions`
+    pub default_extensions: Option<Vec<String>>,
+    /// `--max-size`, in MB
+    pub max_file_size: Option<usize>,
+    /// `--threads`
     pub parallel_jobs: Option<usize>,
-    pub default_extensions: Vec<String>,
+    /// `--safety-policy`
+    pub safety_policy: Option<SafetyPolicy>,
+    /// Files per chunk in benchmark simulations
+    pub chunk_size: Option<usize>,
 }
 
 #[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct DisplayConfig {
     #[serde(skip)]
     pub progress_style: Option<indicatif::ProgressStyle>,
@@ -59,11 +125,19 @@ impl Default for DisplayConfig {
     }
 }
 
+/// Files to leave out of every walk, from the `[ignore]` section
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct IgnoreConfig {
+    /// Gitignore-style patterns to exclude (e.g. `node_modules`, `*.min.js`)
     pub patterns: Vec<String>,
+    /// Skip binary files when searching content, as `--skip-binary` does
     pub binary_files: bool,
-    pub hidden_files: bool,
+    /// Skip hidden files and directories unless `--hidden` is given; unset leaves it to
+    /// the command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden_files: Option<bool>,
+    /// Skip files smaller than this many bytes
     pub min_file_size: Option<u64>,
 }
 
@@ -71,14 +145,32 @@ impl Default for IgnoreConfig {
     fn default() -> Self {
         Self {
             patterns: vec!["node_modules".to_string(), ".git".to_string()],
-            binary_files: true,
-            hidden_files: true,
+            binary_files: false,
+            hidden_files: None,
             min_file_size: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Overrides for file classification, from the `[file_types]` section. Extensions may
+/// be given with or without the leading dot.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileTypesConfig {
+    /// Extensions always searched in full
+    pub always: Vec<String>,
+    /// Extensions searched in their own mode (e.g. metadata) when under the size limit
+    pub conditional: Vec<String>,
+    /// Extensions skipped unless the file type strategy asks for more
+    pub skip: Vec<String>,
+    /// Extensions never searched
+    pub never: Vec<String>,
+    /// Size limit in bytes per extension
+    pub size_limits: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct PerformanceConfig {
     #[serde(default = "default_mmap_threshold")]
     pub mmap_threshold_mb: u64,
@@ -127,49 +219,84 @@ impl Default for PerformanceConfig {
 }
 
 impl Config {
-    #[allow(dead_code)]
-    pub fn load() -> RfgrepResult<Self> {
-        let config_path =
-            Self::find_config_path().map_err(|e| RfgrepError::Other(e.to_string()))?;
-        if let Some(path) = config_path {
-            let content = fs::read_to_string(&path)
-                .map_err(RfgrepError::Io)
-                .map_err(|e| {
-                    RfgrepError::Other(format!(
-                        "Failed to read config file: {}: {e}",
-                        path.display()
-                    ))
-                })?;
-
-            toml::from_str(&content)
-                .map_err(|e| RfgrepError::Other(format!("Failed to parse config file: {e}")))
-        } else {
-            Ok(Self::default())
+    /// Load and merge the config files that apply in the current directory, then
+    /// overlay `profile`
+    pub fn load(profile: Option<&str>) -> RfgrepResult<Self> {
+        let dir = std::env::current_dir().map_err(RfgrepError::Io)?;
+        Self::load_from(&config_sources(&dir), profile)
+    }
+
+    /// Merge `sources` in order, each overriding the settings it repeats, then
+    /// overlay `profile`
+    pub fn load_from(sources: &[PathBuf], profile: Option<&str>) -> RfgrepResult<Self> {
+        let mut merged = toml::Table::new();
+        for path in sources {
+            merge_tables(&mut merged, read_config_file(path)?);
+        }
+        if let Some(name) = profile {
+            let overlay = merged
+                .get("profiles")
+                .and_then(|profiles| profiles.get(name))
+                .and_then(toml::Value::as_table)
+                .cloned()
+                .ok_or_else(|| unknown_profile(name, &merged))?;
+            merge_tables(&mut merged, overlay);
         }
+        merged
+            .try_into()
+            .map_err(|e| RfgrepError::Config(format!("Invalid config: {e}")))
     }
 
-    #[allow(dead_code)]
-    fn find_config_path() -> RfgrepResult<Option<PathBuf>> {
-        if let Some(xdg_config) = dirs::config_dir() {
-            let xdg_path = xdg_config.join("rfgrep/config.toml");
-            if xdg_path.exists() {
-                return Ok(Some(xdg_path));
+    /// Fill the flags that were not given on the command line from the `[search]`
+    /// section
+    pub fn apply_to(&self, cli: &mut Cli, matches: &ArgMatches) {
+        let search = &self.search;
+        cli.max_size = cli.max_size.or(search.max_file_size);
+        cli.threads = cli.threads.or(search.parallel_jobs);
+        if let Some(policy) = &search.safety_policy {
+            if !from_command_line(matches, "safety_policy") {
+                cli.safety_policy = policy.clone();
             }
         }
 
-        if let Some(home) = dirs::home_dir() {
-            let home_path = home.join(".rfgrep.toml");
-            if home_path.exists() {
-                return Ok(Some(home_path));
+        let Some(matches) = matches.subcommand_matches("search") else {
+            return;
+        };
+        let Commands::Search {
+            mode,
+            context_lines,
+            recursive,
+            no_recursive,
+            output_format,
+            include_extensions,
+            ..
+        } = &mut cli.command
+        else {
+            return;
+        };
+        if let Some(default_mode) = &search.default_mode {
+            if !from_command_line(matches, "mode") {
+                *mode = default_mode.clone();
             }
         }
-
-        let current_path = Path::new(".rfgrep.toml");
-        if current_path.exists() {
-            return Ok(Some(current_path.to_path_buf()));
+        if let Some(lines) = search.context_lines {
+            if !from_command_line(matches, "context_lines") {
+                *context_lines = lines;
+            }
+        }
+        if let Some(config_recursive) = search.recursive {
+            if !from_command_line(matches, "recursive") && !*no_recursive {
+                *recursive = config_recursive;
+            }
+        }
+        if let Some(format) = &search.output_format {
+            if !from_command_line(matches, "output_format") {
+                *output_format = format.clone();
+            }
+        }
+        if include_extensions.is_none() {
+            *include_extensions = search.default_extensions.clone();
         }
-
-        Ok(None)
     }
 
     #[allow(dead_code)]
@@ -187,20 +314,210 @@ impl Config {
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            search: SearchConfig {
-                default_mode: SearchMode::Regex,
-                context_lines: 2,
-                max_file_size: Some(100),
-                chunk_size: 100,
-                parallel_jobs: None,
-                default_extensions: vec![],
-            },
-            display: DisplayConfig::default(),
-            ignore: IgnoreConfig::default(),
-            performance: PerformanceConfig::default(),
+/// Config files that apply in `dir`, lowest precedence first: the user config
+/// (`$XDG_CONFIG_HOME/rfgrep/config.toml`, then `~/.rfgrep.toml`) and every
+/// `.rfgrep.toml` from the filesystem root down to `dir`
+pub fn config_sources(dir: &Path) -> Vec<PathBuf> {
+    let user = [
+        dirs::config_dir().map(|config| config.join("rfgrep/config.toml")),
+        dirs::home_dir().map(|home| home.join(PROJECT_CONFIG)),
+    ];
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let mut project: Vec<PathBuf> = dir
+        .ancestors()
+        .map(|ancestor| ancestor.join(PROJECT_CONFIG))
+        .collect();
+    project.reverse();
+
+    let mut sources = Vec::new();
+    for candidate in user.into_iter().flatten().chain(project) {
+        // The home file is also an ancestor's file when working under $HOME
+        if let Ok(path) = candidate.canonicalize() {
+            if path.is_file() && !sources.contains(&path) {
+                sources.push(path);
+            }
         }
     }
+    sources
+}
+
+/// The user config file written by `rfgrep config init --global`
+pub fn user_config_path() -> RfgrepResult<PathBuf> {
+    dirs::config_dir()
+        .map(|config| config.join("rfgrep/config.toml"))
+        .ok_or_else(|| RfgrepError::Other("Cannot determine the config directory".to_string()))
+}
+
+/// Parse the command line, filling the flags it leaves unset from the config files
+/// and `--profile`. `rfgrep config` is parsed without them, so it can report a
+/// broken file.
+pub fn parse_cli<I, T>(args: I) -> RfgrepResult<Cli>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = Cli::command().get_matches_from(args);
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if !cli.no_config && !matches!(cli.command, Commands::Config { .. }) {
+        Config::load(cli.profile.as_deref())?.apply_to(&mut cli, &matches);
+    }
+    Ok(cli)
+}
+
+fn from_command_line(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+/// Read one config file, checking it and each of its profiles against the schema
+fn read_config_file(path: &Path) -> RfgrepResult<toml::Table> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        RfgrepError::Config(format!(
+            "Failed to read config file {}: {e}",
+            path.display()
+        ))
+    })?;
+    let invalid = |e: &dyn fmt::Display| {
+        RfgrepError::Config(format!("Invalid config file {}: {e}", path.display()))
+    };
+    let config: Config = toml::from_str(&content).map_err(|e| invalid(&e))?;
+    for (name, profile) in config.profiles {
+        if profile.contains_key("profiles") {
+            return Err(invalid(&format!("profile '{name}' cannot define profiles")));
+        }
+        profile
+            .try_into::<Config>()
+            .map_err(|e| invalid(&format!("profile '{name}': {e}")))?;
+    }
+    toml::from_str(&content).map_err(|e| invalid(&e))
+}
+
+fn unknown_profile(name: &str, merged: &toml::Table) -> RfgrepError {
+    let available: Vec<&str> = merged
+        .get("profiles")
+        .and_then(toml::Value::as_table)
+        .map(|profiles| profiles.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    if available.is_empty() {
+        RfgrepError::Config(format!(
+            "Unknown profile '{name}': no profiles are configured"
+        ))
+    } else {
+        RfgrepError::Config(format!(
+            "Unknown profile '{name}' (available: {})",
+            available.join(", ")
+        ))
+    }
+}
+
+/// Merge `overlay` into `base`: tables merge key by key, and any other value
+/// replaces the inherited one
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_closer_files_and_profiles_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let global = write(
+            temp_dir.path(),
+            "global.toml",
+            "[search]\ncontext_lines = 3\nrecursive = true\n\n[ignore]\npatterns = [\"target\"]\n",
+        );
+        let project = write(
+            temp_dir.path(),
+            "project.toml",
+            "[search]\ncontext_lines = 1\n\n[profiles.ci.search]\noutput_format = \"json\"\n",
+        );
+
+        let config = Config::load_from(&[global.clone(), project.clone()], None).unwrap();
+        assert_eq!(config.search.context_lines, Some(1));
+        assert_eq!(config.search.recursive, Some(true));
+        assert_eq!(config.ignore.patterns, vec!["target"]);
+        assert_eq!(config.search.output_format, None);
+
+        let config = Config::load_from(&[global.clone(), project.clone()], Some("ci")).unwrap();
+        assert_eq!(config.search.output_format, Some(OutputFormat::Json));
+
+        let error = Config::load_from(&[global, project], Some("nightly")).unwrap_err();
+        assert!(error.to_string().contains("available: ci"));
+    }
+
+    #[test]
+    fn test_unknown_keys_name_the_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = write(temp_dir.path(), "bad.toml", "[search]\ncontext = 2\n");
+        let error = Config::load_from(&[path], None).unwrap_err().to_string();
+        assert!(error.contains("bad.toml"));
+        assert!(error.contains("unknown field `context`"));
+    }
+
+    #[test]
+    fn test_template_is_valid() {
+        let config: Config = toml::from_str(CONFIG_TEMPLATE).unwrap();
+        assert_eq!(config.ignore.patterns, vec!["node_modules", ".git"]);
+        assert!(!config.ignore.binary_files);
+        assert!(!IgnoreConfig::default().binary_files);
+    }
+
+    #[test]
+    fn test_command_line_wins_over_config() {
+        let config: Config = toml::from_str(
+            "[search]\ncontext_lines = 4\ndefault_mode = \"regex\"\nrecursive = true\n",
+        )
+        .unwrap();
+        let parse = |args: &[&str]| {
+            let matches = Cli::command().get_matches_from(args);
+            let mut cli = Cli::from_arg_matches(&matches).unwrap();
+            config.apply_to(&mut cli, &matches);
+            cli
+        };
+
+        let Commands::Search {
+            context_lines,
+            mode,
+            recursive,
+            ..
+        } = parse(&["rfgrep", "search", "x"]).command
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            (context_lines, mode, recursive),
+            (4, SearchMode::Regex, true)
+        );
+
+        let Commands::Search { context_lines, .. } =
+            parse(&["rfgrep", "search", "x", "--context-lines", "0"]).command
+        else {
+            unreachable!()
+        };
+        assert_eq!(context_lines, 0);
+
+        let Commands::Search { recursive, .. } =
+            parse(&["rfgrep", "search", "x", "--no-recursive"]).command
+        else {
+            unreachable!()
+        };
+        assert!(!recursive);
+    }
 }
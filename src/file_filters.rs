@@ -0,0 +1,259 @@
+//! Metadata predicates applied while walking, before any file content is read.
+//!
+//! These back `--changed-within`, `--changed-before`, `--newer`, `--owner`, `--perm`
+//! and `--executable`. Directories are never filtered, only the files inside them.
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Permission bits a file must have set or clear, parsed from `--perm`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PermFilter {
+    /// Bits that must all be set
+    set: u32,
+    /// Bits that must all be clear
+    clear: u32,
+    /// Exact permission bits, for a plain octal mode
+    exact: Option<u32>,
+}
+
+impl PermFilter {
+    /// Parse a mode in `find -perm` style: an octal mode matches exactly (`644`),
+    /// `-` before an octal mode requires all of its bits (`-022`), and symbolic
+    /// clauses such as `o+w`, `ug-x` or `u=rw,go-w` require bits set (`+`), clear
+    /// (`-`) or exactly as given for those classes (`=`). A leading `-` before a
+    /// symbolic mode is accepted for `find` compatibility.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (all_bits, mode) = match value.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, value),
+        };
+        if mode.is_empty() {
+            return Err("empty permission mode".to_string());
+        }
+
+        if mode.bytes().all(|b| b.is_ascii_digit()) {
+            let bits = u32::from_str_radix(mode, 8)
+                .ok()
+                .filter(|bits| *bits <= 0o7777)
+                .ok_or_else(|| format!("invalid octal mode '{mode}'"))?;
+            return Ok(if all_bits {
+                Self {
+                    set: bits,
+                    ..Self::default()
+                }
+            } else {
+                Self {
+                    exact: Some(bits),
+                    ..Self::default()
+                }
+            });
+        }
+
+        let mut filter = Self::default();
+        for clause in mode.split(',') {
+            let op_at = clause.find(['+', '-', '=']).ok_or_else(|| {
+                format!("invalid permission clause '{clause}' (expected e.g. o+w)")
+            })?;
+            let (who, rest) = clause.split_at(op_at);
+            let (op, perms) = rest.split_at(1);
+
+            let mut classes = 0;
+            for c in who.chars() {
+                classes |= match c {
+                    'u' => 0o700,
+                    'g' => 0o070,
+                    'o' => 0o007,
+                    'a' => 0o777,
+                    _ => return Err(format!("invalid permission class '{c}' in '{clause}'")),
+                };
+            }
+            if classes == 0 {
+                classes = 0o777;
+            }
+
+            let mut bits = 0;
+            for c in perms.chars() {
+                bits |= match c {
+                    'r' => 0o444,
+                    'w' => 0o222,
+                    'x' => 0o111,
+                    _ => return Err(format!("invalid permission '{c}' in '{clause}'")),
+                };
+            }
+            let bits = bits & classes;
+
+            match op {
+                "+" => filter.set |= bits,
+                "-" => filter.clear |= bits,
+                _ => {
+                    filter.set |= bits;
+                    filter.clear |= classes & !bits;
+                }
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Whether permission bits `mode` satisfy the filter
+    pub fn matches(&self, mode: u32) -> bool {
+        let mode = mode & 0o7777;
+        match self.exact {
+            Some(exact) => mode == exact,
+            None => mode & self.set == self.set && mode & self.clear == 0,
+        }
+    }
+}
+
+/// Metadata predicates a file must satisfy to be walked
+#[derive(Debug, Clone, Default)]
+pub struct FileFilters {
+    /// Keep files modified at or after this time (`--changed-within`, `--newer`)
+    pub modified_after: Option<SystemTime>,
+    /// Keep files modified before this time (`--changed-before`)
+    pub modified_before: Option<SystemTime>,
+    /// Keep files owned by this user id (`--owner`)
+    pub owner: Option<u32>,
+    /// Keep files whose permissions match (`--perm`)
+    pub perm: Option<PermFilter>,
+    /// Keep only executable files (`--executable`)
+    pub executable: bool,
+}
+
+impl FileFilters {
+    pub fn is_empty(&self) -> bool {
+        self.modified_after.is_none()
+            && self.modified_before.is_none()
+            && self.owner.is_none()
+            && self.perm.is_none()
+            && !self.executable
+    }
+
+    /// Require files to be modified at or after `time`, keeping the later bound
+    pub fn modified_after(mut self, time: SystemTime) -> Self {
+        self.modified_after = Some(self.modified_after.map_or(time, |t| t.max(time)));
+        self
+    }
+
+    /// Whether a file with `metadata` passes every predicate
+    pub fn matches(&self, metadata: &Metadata) -> bool {
+        if self.modified_after.is_some() || self.modified_before.is_some() {
+            let Ok(modified) = metadata.modified() else {
+                return false;
+            };
+            if self.modified_after.is_some_and(|after| modified < after) {
+                return false;
+            }
+            if self
+                .modified_before
+                .is_some_and(|before| modified >= before)
+            {
+                return false;
+            }
+        }
+        if self
+            .owner
+            .is_some_and(|uid| owner_of(metadata) != Some(uid))
+        {
+            return false;
+        }
+        let mode = mode_of(metadata);
+        if self.perm.is_some_and(|perm| !perm.matches(mode)) {
+            return false;
+        }
+        if self.executable && mode & 0o111 == 0 {
+            return false;
+        }
+        true
+    }
+}
+
+/// Modification time of `path`, for `--newer FILE`
+pub fn modified_time(path: &Path) -> RfgrepResult<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map_err(|e| RfgrepError::Other(format!("Cannot read {}: {e}", path.display())))
+}
+
+/// Resolve `--owner` (a user name or numeric uid) to a uid
+#[cfg(unix)]
+pub fn resolve_owner(user: &str) -> RfgrepResult<u32> {
+    if let Ok(uid) = user.parse() {
+        return Ok(uid);
+    }
+    let name = std::ffi::CString::new(user)
+        .map_err(|_| RfgrepError::Other(format!("Invalid user name '{user}'")))?;
+    // SAFETY: `name` is a valid C string and the returned record is read before any
+    // other call that could overwrite it
+    let passwd = unsafe { libc::getpwnam(name.as_ptr()) };
+    if passwd.is_null() {
+        return Err(RfgrepError::Other(format!("Unknown user '{user}'")));
+    }
+    Ok(unsafe { (*passwd).pw_uid })
+}
+
+#[cfg(not(unix))]
+pub fn resolve_owner(_user: &str) -> RfgrepResult<u32> {
+    Err(RfgrepError::Other(
+        "--owner is only supported on Unix".to_string(),
+    ))
+}
+
+#[cfg(unix)]
+fn owner_of(metadata: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.uid())
+}
+
+#[cfg(not(unix))]
+fn owner_of(_metadata: &Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn mode_of(metadata: &Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode()
+}
+
+/// Approximate Unix permission bits from the read-only flag
+#[cfg(not(unix))]
+fn mode_of(metadata: &Metadata) -> u32 {
+    if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o666
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_symbolic_perm() {
+        let world_writable = PermFilter::parse("-o+w").unwrap();
+        assert!(world_writable.matches(0o666));
+        assert!(!world_writable.matches(0o644));
+
+        let private = PermFilter::parse("go-rwx").unwrap();
+        assert!(private.matches(0o600));
+        assert!(!private.matches(0o640));
+
+        let exact_user = PermFilter::parse("u=rw").unwrap();
+        assert!(exact_user.matches(0o644));
+        assert!(!exact_user.matches(0o744));
+    }
+
+    #[test]
+    fn test_parse_octal_perm() {
+        assert!(PermFilter::parse("644").unwrap().matches(0o100644));
+        assert!(!PermFilter::parse("644").unwrap().matches(0o664));
+        assert!(PermFilter::parse("-022").unwrap().matches(0o777));
+        assert!(!PermFilter::parse("-022").unwrap().matches(0o755));
+        assert!(PermFilter::parse("o+q").is_err());
+        assert!(PermFilter::parse("999").is_err());
+    }
+}
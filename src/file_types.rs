@@ -1,5 +1,10 @@
-// Removed unused imports
+use crate::config::FileTypesConfig;
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::language_detection::detect_file_type;
+use crate::summary::SkipReason;
+use ignore::types::{FileTypeDef, Types, TypesBuilder};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs::Metadata;
 use std::path::Path;
 
@@ -20,7 +25,49 @@ pub enum SearchMode {
     Structured, // Search structured content (JSON, XML)
 }
 
+impl fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SearchMode::FullText => "full text",
+            SearchMode::Metadata => "metadata",
+            SearchMode::Filename => "filename",
+            SearchMode::Structured => "structured",
+        })
+    }
+}
+
+impl fmt::Display for SearchDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchDecision::Search(mode) => write!(f, "search ({mode})"),
+            SearchDecision::Skip(reason) => write!(f, "skip: {reason}"),
+            SearchDecision::Conditional(mode, reason) => {
+                write!(f, "conditional ({mode}): {reason}")
+            }
+        }
+    }
+}
+
+/// Size limit for searched extensions without an entry in `size_limits`
+const DEFAULT_SIZE_LIMIT: u64 = 50 * 1024 * 1024;
+
+/// Size limit for conditionally searched extensions without an entry in `size_limits`
+const DEFAULT_CONDITIONAL_SIZE_LIMIT: u64 = 10 * 1024 * 1024;
+
+/// A [`SearchDecision`] together with what produced it, as shown by `rfgrep explain`
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    pub decision: SearchDecision,
+    /// The extension set, name or content detection, or MIME sniff that decided
+    pub rule: String,
+    /// The size limit that was checked, if any
+    pub size_limit: Option<String>,
+    /// Category of a [`SearchDecision::Skip`]
+    pub skip_reason: Option<SkipReason>,
+}
+
 /// File type classifier that determines how to handle different file types
+#[derive(Clone)]
 pub struct FileTypeClassifier {
     always_search: HashSet<String>,
     conditional_search: HashSet<String>,
@@ -28,6 +75,10 @@ pub struct FileTypeClassifier {
     never_search: HashSet<String>,
     size_limits: HashMap<String, u64>,
     search_modes: HashMap<String, SearchMode>,
+    /// Extensions placed in their set by the `[file_types]` config section
+    configured: HashSet<String>,
+    /// Extensions whose size limit comes from the `[file_types]` config section
+    configured_limits: HashSet<String>,
 }
 
 impl FileTypeClassifier {
@@ -37,6 +88,7 @@ impl FileTypeClassifier {
                 // Plain text
                 "txt",
                 "md",
+                "markdown",
                 "rst",
                 "org",
                 "tex",
@@ -46,28 +98,43 @@ impl FileTypeClassifier {
                 // Source code
                 "rs",
                 "py",
+                "pyi",
+                "pyw",
                 "js",
+                "mjs",
+                "cjs",
                 "ts",
                 "go",
                 "java",
                 "cpp",
+                "cc",
+                "cxx",
                 "c",
                 "h",
                 "hpp",
+                "hh",
                 "cs",
                 "php",
                 "rb",
+                "rake",
+                "gemspec",
                 "swift",
                 "kt",
+                "kts",
                 "scala",
                 "dart",
                 "r",
                 "lua",
+                "pl",
+                "pm",
+                "groovy",
+                "gradle",
                 "sh",
                 "bash",
                 "zsh",
                 "fish",
                 "ps1",
+                "psm1",
                 "bat",
                 "cmd",
                 // Web technologies
@@ -95,6 +162,8 @@ impl FileTypeClassifier {
                 "properties",
                 "dockerfile",
                 "makefile",
+                "mk",
+                "mak",
                 // Data formats
                 "csv",
                 "tsv",
@@ -208,85 +277,207 @@ impl FileTypeClassifier {
                 ("jpg".to_string(), SearchMode::Metadata),
                 ("png".to_string(), SearchMode::Metadata),
             ]),
+            configured: HashSet::new(),
+            configured_limits: HashSet::new(),
+        }
+    }
+
+    /// Apply the `[file_types]` config section. Each listed extension is moved into
+    /// that set (when listed twice, the stricter set wins), and `size_limits` add or
+    /// replace per-extension limits.
+    pub fn with_config(mut self, config: &FileTypesConfig) -> Self {
+        let lists = [
+            &config.always,
+            &config.conditional,
+            &config.skip,
+            &config.never,
+        ];
+        for (target, extensions) in lists.into_iter().enumerate() {
+            for ext in extensions {
+                let ext = normalize_extension(ext);
+                let sets = [
+                    &mut self.always_search,
+                    &mut self.conditional_search,
+                    &mut self.skip_by_default,
+                    &mut self.never_search,
+                ];
+                for (index, set) in sets.into_iter().enumerate() {
+                    if index == target {
+                        set.insert(ext.clone());
+                    } else {
+                        set.remove(&ext);
+                    }
+                }
+                self.configured.insert(ext);
+            }
+        }
+        for (ext, limit) in &config.size_limits {
+            let ext = normalize_extension(ext);
+            self.size_limits.insert(ext.clone(), *limit);
+            self.configured_limits.insert(ext);
         }
+        self
     }
 
     /// Determine if a file should be searched and how
     pub fn should_search(&self, path: &Path, metadata: &Metadata) -> SearchDecision {
+        self.explain(path, metadata).decision
+    }
+
+    /// Like [`should_search`](Self::should_search), also reporting the rule and size
+    /// limit behind the decision
+    pub fn explain(&self, path: &Path, metadata: &Metadata) -> Explanation {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|s| s.to_ascii_lowercase())
+            .unwrap_or_default();
+        if let Some(explanation) = self.explain_by_extension(&ext, metadata.len()) {
+            return explanation;
+        }
+
+        // Scripts and well-known files without a telling extension
+        if let Some(detected) = detect_file_type(path) {
+            let rule = format!("detected as `{detected}` from its name, shebang or modeline");
+            return self.check_size_limits(&ext, metadata.len(), SearchMode::FullText, rule);
+        }
+
+        // Unknown extension - use MIME type detection
+        self.classify_by_mime(path, metadata)
+    }
+
+    /// Like [`explain`](Self::explain) for a file of `size` bytes that is not on disk,
+    /// such as a git blob. Only the extension rules apply; any other file is searched,
+    /// and binary contents are skipped once read.
+    pub fn explain_stored(&self, path: &Path, size: u64) -> Explanation {
         let ext = path
             .extension()
             .and_then(|e| e.to_str())
             .map(|s| s.to_ascii_lowercase())
             .unwrap_or_default();
+        self.explain_by_extension(&ext, size).unwrap_or_else(|| {
+            let rule = "stored file; classified by its contents when read".to_string();
+            self.check_size_limits(&ext, size, SearchMode::FullText, rule)
+        })
+    }
 
+    /// Decision for an extension in one of the configured sets
+    fn explain_by_extension(&self, ext: &str, file_size: u64) -> Option<Explanation> {
         // Never search these
-        if self.never_search.contains(&ext) {
-            return SearchDecision::Skip(format!("Never search file type: {}", ext));
+        if self.never_search.contains(ext) {
+            return Some(Explanation {
+                decision: SearchDecision::Skip(format!("Never search file type: {}", ext)),
+                rule: self.set_rule("never_search", ext),
+                size_limit: None,
+                skip_reason: Some(SkipReason::NeverSearch),
+            });
         }
 
         // Always search these
-        if self.always_search.contains(&ext) {
-            return self.check_size_limits(&ext, metadata, SearchMode::FullText);
+        if self.always_search.contains(ext) {
+            let rule = self.set_rule("always_search", ext);
+            return Some(self.check_size_limits(ext, file_size, SearchMode::FullText, rule));
         }
 
         // Conditional search
-        if self.conditional_search.contains(&ext) {
+        if self.conditional_search.contains(ext) {
             let search_mode = self
                 .search_modes
-                .get(&ext)
+                .get(ext)
                 .cloned()
                 .unwrap_or(SearchMode::Metadata);
-            return self.check_conditional_search(&ext, metadata, search_mode);
+            let rule = self.set_rule("conditional_search", ext);
+            return Some(self.check_conditional_search(ext, file_size, search_mode, rule));
         }
 
         // Skip by default
-        if self.skip_by_default.contains(&ext) {
-            return SearchDecision::Skip(format!("Skip by default: {}", ext));
+        if self.skip_by_default.contains(ext) {
+            return Some(Explanation {
+                decision: SearchDecision::Skip(format!("Skip by default: {}", ext)),
+                rule: self.set_rule("skip_by_default", ext),
+                size_limit: None,
+                skip_reason: Some(SkipReason::SkipByDefault),
+            });
         }
+        None
+    }
 
-        // Unknown extension - use MIME type detection
-        self.classify_by_mime(path, metadata)
+    /// Rule text for an extension found in one of the sets
+    fn set_rule(&self, set: &str, ext: &str) -> String {
+        let source = if self.configured.contains(ext) {
+            "config"
+        } else {
+            "built-in"
+        };
+        format!("extension `.{ext}` is in {set} ({source})")
+    }
+
+    /// The size limit for `ext` and a description of where it comes from
+    fn size_limit(&self, ext: &str, default: u64) -> (u64, String) {
+        match self.size_limits.get(ext) {
+            Some(&limit) => {
+                let source = if self.configured_limits.contains(ext) {
+                    "config"
+                } else {
+                    "built-in"
+                };
+                (limit, format!("{limit} bytes for .{ext} ({source})"))
+            }
+            None => (default, format!("{default} bytes (default)")),
+        }
     }
 
     fn check_size_limits(
         &self,
         ext: &str,
-        metadata: &Metadata,
+        file_size: u64,
         default_mode: SearchMode,
-    ) -> SearchDecision {
-        let file_size = metadata.len();
-        let limit = self.size_limits.get(ext).unwrap_or(&(50 * 1024 * 1024));
+        rule: String,
+    ) -> Explanation {
+        let (limit, size_limit) = self.size_limit(ext, DEFAULT_SIZE_LIMIT);
 
-        if file_size > *limit {
+        let decision = if file_size > limit {
             SearchDecision::Skip(format!(
                 "File too large: {} bytes > {} bytes (limit for .{})",
                 file_size, limit, ext
             ))
         } else {
             SearchDecision::Search(default_mode)
+        };
+        Explanation {
+            skip_reason: (file_size > limit).then_some(SkipReason::SizeLimit),
+            decision,
+            rule,
+            size_limit: Some(size_limit),
         }
     }
 
     fn check_conditional_search(
         &self,
         ext: &str,
-        metadata: &Metadata,
+        file_size: u64,
         search_mode: SearchMode,
-    ) -> SearchDecision {
-        let file_size = metadata.len();
-        let limit = self.size_limits.get(ext).unwrap_or(&(10 * 1024 * 1024));
+        rule: String,
+    ) -> Explanation {
+        let (limit, size_limit) = self.size_limit(ext, DEFAULT_CONDITIONAL_SIZE_LIMIT);
 
-        if file_size > *limit {
+        let decision = if file_size > limit {
             SearchDecision::Skip(format!(
                 "Conditional file too large: {} bytes > {} bytes (limit for .{})",
                 file_size, limit, ext
             ))
         } else {
             SearchDecision::Conditional(search_mode, format!("Conditional search for .{}", ext))
+        };
+        Explanation {
+            skip_reason: (file_size > limit).then_some(SkipReason::SizeLimit),
+            decision,
+            rule,
+            size_limit: Some(size_limit),
         }
     }
 
-    fn classify_by_mime(&self, path: &Path, metadata: &Metadata) -> SearchDecision {
+    fn classify_by_mime(&self, path: &Path, metadata: &Metadata) -> Explanation {
         // Use infer crate for MIME type detection
         if let Ok(Some(kind)) = infer::get_from_path(path) {
             let mime = kind.mime_type();
@@ -294,32 +485,66 @@ impl FileTypeClassifier {
         }
 
         // Fallback: check if file is likely text by reading first few bytes
-        if self.is_likely_text_file(path) {
+        let decision = if self.is_likely_text_file(path) {
             SearchDecision::Search(SearchMode::FullText)
         } else {
             SearchDecision::Skip("Unknown file type, not text-like".to_string())
+        };
+        Explanation {
+            skip_reason: matches!(decision, SearchDecision::Skip(_))
+                .then_some(SkipReason::UnknownType),
+            decision,
+            rule: "no known file signature; the first 1024 bytes were checked for text".to_string(),
+            size_limit: None,
         }
     }
 
-    fn classify_by_mime_type(&self, mime: &str, metadata: &Metadata) -> SearchDecision {
-        match mime {
+    fn classify_by_mime_type(&self, mime: &str, metadata: &Metadata) -> Explanation {
+        let rule = format!("MIME sniff: {mime}");
+        let mut skip_reason = None;
+        let decision = match mime {
             m if m.starts_with("text/") => SearchDecision::Search(SearchMode::FullText),
             m if m.starts_with("application/json") || m.starts_with("application/xml") => {
                 SearchDecision::Search(SearchMode::Structured)
             }
             m if m.starts_with("application/pdf") => {
-                self.check_conditional_search("pdf", metadata, SearchMode::Metadata)
+                return self.check_conditional_search(
+                    "pdf",
+                    metadata.len(),
+                    SearchMode::Metadata,
+                    rule,
+                )
             }
             m if m.starts_with("application/zip") || m.starts_with("application/x-tar") => {
-                self.check_conditional_search("zip", metadata, SearchMode::Filename)
+                return self.check_conditional_search(
+                    "zip",
+                    metadata.len(),
+                    SearchMode::Filename,
+                    rule,
+                )
             }
             m if m.starts_with("image/") || m.starts_with("video/") || m.starts_with("audio/") => {
-                self.check_conditional_search("media", metadata, SearchMode::Metadata)
+                return self.check_conditional_search(
+                    "media",
+                    metadata.len(),
+                    SearchMode::Metadata,
+                    rule,
+                )
             }
             m if m.starts_with("application/octet-stream") => {
+                skip_reason = Some(SkipReason::Binary);
                 SearchDecision::Skip("Binary file detected".to_string())
             }
-            _ => SearchDecision::Skip(format!("Unknown MIME type: {}", mime)),
+            _ => {
+                skip_reason = Some(SkipReason::UnknownType);
+                SearchDecision::Skip(format!("Unknown MIME type: {}", mime))
+            }
+        };
+        Explanation {
+            decision,
+            rule,
+            size_limit: None,
+            skip_reason,
         }
     }
 
@@ -395,6 +620,233 @@ impl Default for FileTypeClassifier {
     }
 }
 
+/// Named file types for `--type` and `--type-not`, as globs over file names.
+/// Every extension used here is one the classifier searches.
+pub const DEFAULT_TYPES: &[(&str, &[&str])] = &[
+    ("batch", &["*.bat", "*.cmd"]),
+    ("c", &["*.c", "*.h"]),
+    (
+        "config",
+        &[
+            "*.json",
+            "*.yaml",
+            "*.yml",
+            "*.toml",
+            "*.ini",
+            "*.cfg",
+            "*.conf",
+            "*.config",
+            "*.env",
+            "*.properties",
+            ".editorconfig",
+        ],
+    ),
+    ("cpp", &["*.cpp", "*.cc", "*.cxx", "*.hpp", "*.hh", "*.h"]),
+    ("csharp", &["*.cs"]),
+    ("css", &["*.css", "*.scss", "*.sass", "*.less"]),
+    ("csv", &["*.csv", "*.tsv"]),
+    ("dart", &["*.dart"]),
+    (
+        "docker",
+        &[
+            "Dockerfile",
+            "Dockerfile.*",
+            "Containerfile",
+            "*.dockerfile",
+        ],
+    ),
+    (
+        "docs",
+        &[
+            "*.md",
+            "*.markdown",
+            "*.rst",
+            "*.org",
+            "*.adoc",
+            "*.asciidoc",
+            "*.tex",
+            "*.latex",
+            "*.pod",
+            "*.txt",
+            "README",
+            "README.*",
+            "CHANGELOG",
+            "CHANGELOG.*",
+        ],
+    ),
+    ("go", &["*.go"]),
+    ("graphql", &["*.graphql", "*.gql"]),
+    ("groovy", &["*.groovy", "*.gradle", "Jenkinsfile"]),
+    ("html", &["*.html", "*.htm"]),
+    ("java", &["*.java"]),
+    ("js", &["*.js", "*.mjs", "*.cjs", "*.jsx"]),
+    ("json", &["*.json"]),
+    ("kotlin", &["*.kt", "*.kts"]),
+    ("log", &["*.log"]),
+    ("lua", &["*.lua"]),
+    (
+        "make",
+        &["Makefile", "makefile", "GNUmakefile", "*.mk", "*.mak"],
+    ),
+    ("markdown", &["*.md", "*.markdown"]),
+    ("perl", &["*.pl", "*.pm"]),
+    ("php", &["*.php"]),
+    ("powershell", &["*.ps1", "*.psm1"]),
+    ("python", &["*.py", "*.pyi", "*.pyw"]),
+    ("r", &["*.r", "*.R"]),
+    (
+        "ruby",
+        &[
+            "*.rb",
+            "*.rake",
+            "*.gemspec",
+            "Gemfile",
+            "Rakefile",
+            "Vagrantfile",
+            "Podfile",
+            "Brewfile",
+        ],
+    ),
+    ("rust", &["*.rs"]),
+    ("scala", &["*.scala"]),
+    (
+        "shell",
+        &[
+            "*.sh",
+            "*.bash",
+            "*.zsh",
+            "*.fish",
+            ".bashrc",
+            ".bash_profile",
+            ".zshrc",
+            ".profile",
+            "PKGBUILD",
+        ],
+    ),
+    ("sql", &["*.sql"]),
+    ("swift", &["*.swift"]),
+    ("toml", &["*.toml"]),
+    ("ts", &["*.ts", "*.tsx"]),
+    ("txt", &["*.txt"]),
+    ("vue", &["*.vue"]),
+    (
+        "web",
+        &[
+            "*.html", "*.htm", "*.css", "*.scss", "*.sass", "*.less", "*.js", "*.mjs", "*.jsx",
+            "*.ts", "*.tsx", "*.vue", "*.svg",
+        ],
+    ),
+    ("xml", &["*.xml", "*.svg"]),
+    ("yaml", &["*.yaml", "*.yml"]),
+];
+
+/// The built-in type definitions plus `--type-add` additions, each `name:glob` or
+/// `name:include:other,types` to reuse existing definitions
+pub fn type_definitions(additions: &[String]) -> RfgrepResult<TypesBuilder> {
+    let mut builder = TypesBuilder::new();
+    for (name, globs) in DEFAULT_TYPES {
+        for glob in *globs {
+            builder.add(name, glob).map_err(invalid_type)?;
+        }
+    }
+    for definition in additions {
+        builder.add_def(definition).map_err(invalid_type)?;
+    }
+    Ok(builder)
+}
+
+/// `--type` and `--type-not` selection. File names are matched against the type globs;
+/// extensionless files that no glob covers are identified by shebang or modeline instead.
+pub struct TypeFilter {
+    types: Types,
+    /// Types accepted by the selection, including those covered by `include` definitions
+    selected: HashSet<String>,
+    /// Types rejected by `--type-not`
+    negated: HashSet<String>,
+    any_selected: bool,
+    select_all: bool,
+}
+
+impl TypeFilter {
+    /// Filter selecting `types` and rejecting `types_not`, or `None` when neither is given
+    pub fn new(
+        additions: &[String],
+        types: &[String],
+        types_not: &[String],
+    ) -> RfgrepResult<Option<Self>> {
+        if types.is_empty() && types_not.is_empty() {
+            return Ok(None);
+        }
+        let mut builder = type_definitions(additions)?;
+        for name in types {
+            builder.select(name);
+        }
+        for name in types_not {
+            builder.negate(name);
+        }
+        let definitions = builder.definitions();
+        Ok(Some(Self {
+            types: builder.build().map_err(invalid_type)?,
+            selected: covered_types(&definitions, types),
+            negated: covered_types(&definitions, types_not),
+            any_selected: !types.is_empty(),
+            select_all: types.iter().any(|name| name == "all"),
+        }))
+    }
+
+    /// Whether the file at `path` passes the selection
+    pub fn matches(&self, path: &Path) -> bool {
+        let matched = self.types.matched(path, false);
+        if matched.is_whitelist() {
+            return true;
+        }
+        // A glob of a --type-not type matched
+        if matched
+            .inner()
+            .is_some_and(|glob| glob.file_type_def().is_some())
+        {
+            return false;
+        }
+        match detect_file_type(path) {
+            Some(detected) if self.negated.contains(detected) => false,
+            Some(detected) => {
+                !self.any_selected || self.select_all || self.selected.contains(detected)
+            }
+            None => !self.any_selected,
+        }
+    }
+}
+
+/// Every type whose globs are all part of one of the `names` definitions, so a type
+/// defined as `scripts:include:python,shell` also accepts files detected as `python`
+fn covered_types(definitions: &[FileTypeDef], names: &[String]) -> HashSet<String> {
+    let mut covered = HashSet::new();
+    for name in names {
+        let Some(globs) = definitions
+            .iter()
+            .find(|definition| definition.name() == name)
+            .map(|definition| definition.globs())
+        else {
+            continue;
+        };
+        for definition in definitions {
+            if definition.globs().iter().all(|glob| globs.contains(glob)) {
+                covered.insert(definition.name().to_string());
+            }
+        }
+    }
+    covered
+}
+
+/// Config extensions may be written as `rs` or `.rs`, in any case
+fn normalize_extension(ext: &str) -> String {
+    ext.trim_start_matches('.').to_ascii_lowercase()
+}
+
+fn invalid_type(err: ignore::Error) -> RfgrepError {
+    RfgrepError::Other(format!("Invalid file type: {err}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -467,4 +919,99 @@ mod tests {
         let limit = classifier.get_size_limit("pdf").unwrap();
         assert_eq!(limit, 10 * 1024 * 1024);
     }
+
+    #[test]
+    fn test_config_overrides_sets_and_limits() {
+        let config = FileTypesConfig {
+            always: vec![".LOCK".to_string()],
+            never: vec!["txt".to_string()],
+            size_limits: [("rs".to_string(), 4)].into_iter().collect(),
+            ..FileTypesConfig::default()
+        };
+        let classifier = FileTypeClassifier::new().with_config(&config);
+        assert!(classifier.is_always_search("lock"));
+        assert!(!classifier.is_skip_by_default("lock"));
+        assert!(classifier.is_never_search("txt"));
+        assert!(!classifier.is_always_search("txt"));
+
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("main.rs");
+        std::fs::write(&source, "fn main() {}\n").unwrap();
+        let explanation = classifier.explain(&source, &source.metadata().unwrap());
+        assert!(matches!(explanation.decision, SearchDecision::Skip(_)));
+        assert_eq!(
+            explanation.rule,
+            "extension `.rs` is in always_search (built-in)"
+        );
+        assert_eq!(
+            explanation.size_limit.as_deref(),
+            Some("4 bytes for .rs (config)")
+        );
+    }
+
+    #[test]
+    fn test_named_types_use_searched_extensions() {
+        let classifier = FileTypeClassifier::new();
+        for (name, globs) in DEFAULT_TYPES {
+            for ext in globs.iter().filter_map(|glob| glob.strip_prefix("*.")) {
+                assert!(
+                    classifier.is_always_search(&ext.to_ascii_lowercase()),
+                    "type {name} uses unknown extension {ext}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_type_filter() {
+        let rust = TypeFilter::new(&[], &["rust".to_string()], &[])
+            .unwrap()
+            .unwrap();
+        assert!(rust.matches(Path::new("src/lib.rs")));
+        assert!(!rust.matches(Path::new("README.md")));
+
+        let no_docker = TypeFilter::new(&[], &[], &["docker".to_string()])
+            .unwrap()
+            .unwrap();
+        assert!(!no_docker.matches(Path::new("Dockerfile")));
+        assert!(no_docker.matches(Path::new("main.go")));
+
+        let additions = vec!["proto:*.proto".to_string()];
+        let proto = TypeFilter::new(&additions, &["proto".to_string()], &[])
+            .unwrap()
+            .unwrap();
+        assert!(proto.matches(Path::new("api.proto")));
+
+        assert!(TypeFilter::new(&[], &["nope".to_string()], &[]).is_err());
+        assert!(TypeFilter::new(&[], &[], &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_type_filter_detects_extensionless_scripts() {
+        let temp_dir = tempdir().unwrap();
+        let script = temp_dir.path().join("deploy");
+        std::fs::write(&script, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+
+        let python = TypeFilter::new(&[], &["python".to_string()], &[])
+            .unwrap()
+            .unwrap();
+        assert!(python.matches(&script));
+
+        let additions = vec!["scripts:include:python,shell".to_string()];
+        let scripts = TypeFilter::new(&additions, &["scripts".to_string()], &[])
+            .unwrap()
+            .unwrap();
+        assert!(scripts.matches(&script));
+
+        let no_python = TypeFilter::new(&[], &[], &["python".to_string()])
+            .unwrap()
+            .unwrap();
+        assert!(!no_python.matches(&script));
+
+        let metadata = script.metadata().unwrap();
+        assert!(matches!(
+            FileTypeClassifier::new().should_search(&script, &metadata),
+            SearchDecision::Search(SearchMode::FullText)
+        ));
+    }
 }
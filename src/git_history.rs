@@ -0,0 +1,304 @@
+//! Searching git revisions and history without checking anything out.
+//!
+//! `--rev REV` searches the tree of a commit, and `--history` finds the commits in
+//! which matching lines were introduced or removed. File contents come from a
+//! [`BlobReader`] backed by `git cat-file --batch`, so everything works offline on a
+//! local clone and the working tree is never touched.
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::git_scope::git;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// Length of the abbreviated commit ids used in path labels
+const SHORT_COMMIT_LEN: usize = 12;
+
+/// Which revisions to search instead of the working tree
+#[derive(Debug, Clone, Default)]
+pub struct RevisionScope {
+    /// Commit whose tree is searched, or the history tip (`--rev`)
+    pub rev: Option<String>,
+    /// Search commits for added and removed matches (`--history`)
+    pub history: bool,
+}
+
+impl RevisionScope {
+    /// Whether neither option was given, so the working tree should be searched
+    pub fn is_empty(&self) -> bool {
+        self.rev.is_none() && !self.history
+    }
+
+    /// Revision to start from
+    pub fn tip(&self) -> &str {
+        self.rev.as_deref().unwrap_or("HEAD")
+    }
+}
+
+/// Whether a `--history` match was introduced or removed by its commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryChange {
+    Added,
+    Removed,
+}
+
+/// Commit and repository path a match was read from
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RevisionMatch {
+    /// Full commit id
+    pub commit: String,
+    /// Path of the file relative to the search root
+    pub path: PathBuf,
+    /// Set for `--history` matches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change: Option<HistoryChange>,
+}
+
+impl RevisionMatch {
+    /// Label shown in place of the file path, in `git grep` style: `commit:path`,
+    /// prefixed with `+` or `-` for history matches
+    pub fn label(&self, root: &Path) -> PathBuf {
+        let marker = match self.change {
+            Some(HistoryChange::Added) => "+",
+            Some(HistoryChange::Removed) => "-",
+            None => "",
+        };
+        let short = &self.commit[..self.commit.len().min(SHORT_COMMIT_LEN)];
+        PathBuf::from(format!(
+            "{marker}{short}:{}",
+            root.join(&self.path).display()
+        ))
+    }
+}
+
+/// A file in a commit's tree
+#[derive(Debug, Clone)]
+pub struct TreeEntry {
+    /// Path relative to the search root
+    pub path: PathBuf,
+    /// Blob id
+    pub object: String,
+}
+
+/// A file touched by a commit in `--history` mode
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub commit: String,
+    /// Path relative to the search root
+    pub path: PathBuf,
+    /// Blob id in the commit's parent, unless the commit added the file
+    pub old: Option<String>,
+    /// Blob id in the commit, unless the commit deleted the file
+    pub new: Option<String>,
+}
+
+/// Handle on the repository containing a search root
+#[derive(Debug, Clone)]
+pub struct Repository {
+    root: PathBuf,
+}
+
+impl Repository {
+    /// Open the repository containing the directory `root`
+    pub fn open(root: &Path) -> RfgrepResult<Self> {
+        if !root.is_dir() {
+            return Err(RfgrepError::Other(format!(
+                "Searching revisions needs a directory inside a repository, got {}",
+                root.display()
+            )));
+        }
+        git(root, &["rev-parse", "--git-dir"])?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Resolve `rev` to a full commit id
+    pub fn resolve(&self, rev: &str) -> RfgrepResult<String> {
+        let spec = format!("{rev}^{{commit}}");
+        Ok(
+            git_text(&self.root, &["rev-parse", "--verify", "--quiet", &spec])
+                .map_err(|_| RfgrepError::Other(format!("Unknown revision '{rev}'")))?
+                .trim()
+                .to_string(),
+        )
+    }
+
+    /// Every blob under the search root in `commit`'s tree
+    pub fn tree(&self, commit: &str) -> RfgrepResult<Vec<TreeEntry>> {
+        let listing = git(&self.root, &["ls-tree", "-r", "-z", commit])?;
+        Ok(listing
+            .split(|&b| b == 0)
+            .filter_map(|record| {
+                let record = String::from_utf8_lossy(record);
+                let (meta, path) = record.split_once('\t')?;
+                let mut fields = meta.split(' ');
+                let (_mode, kind, object) = (fields.next()?, fields.next()?, fields.next()?);
+                (kind == "blob").then(|| TreeEntry {
+                    path: PathBuf::from(path),
+                    object: object.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Files whose number of occurrences of `pattern` changed in some commit
+    /// reachable from `tip`, newest first (like `git log -S`). The commits and their
+    /// files come from a single `git log --raw` run.
+    pub fn history(
+        &self,
+        tip: &str,
+        pattern: &str,
+        regex: bool,
+        case_sensitive: bool,
+    ) -> RfgrepResult<Vec<HistoryEntry>> {
+        let pickaxe = format!("-S{pattern}");
+        let mut args = vec![
+            "log",
+            "--format=%H",
+            "--raw",
+            "-z",
+            "--no-abbrev",
+            "--no-renames",
+            "--no-merges",
+            "--relative",
+            &pickaxe,
+        ];
+        if regex {
+            args.push("--pickaxe-regex");
+        }
+        if !case_sensitive {
+            args.push("-i");
+        }
+        args.extend([tip, "--", "."]);
+        Ok(parse_raw_log(&git(&self.root, &args)?))
+    }
+
+    /// Start a reader for blob contents
+    pub fn blob_reader(&self) -> RfgrepResult<BlobReader> {
+        BlobReader::new(&self.root)
+    }
+}
+
+/// Parse `git log --format=%H --raw -z` output: each commit id is followed by one
+/// `:<modes> <old> <new> <status>` record and path per changed file
+fn parse_raw_log(log: &[u8]) -> Vec<HistoryEntry> {
+    let blob = |id: &str| (!id.bytes().all(|b| b == b'0')).then(|| id.to_string());
+    let mut entries = Vec::new();
+    let mut commit = String::new();
+    let mut fields = log.split(|&b| b == 0);
+    while let Some(field) = fields.next() {
+        let field = String::from_utf8_lossy(field);
+        let field = field.trim_start_matches('\n');
+        if let Some(record) = field.strip_prefix(':') {
+            let Some(path) = fields.next() else {
+                break;
+            };
+            let ids: Vec<&str> = record.split(' ').collect();
+            if let [_, _, old, new, _] = ids[..] {
+                entries.push(HistoryEntry {
+                    commit: commit.clone(),
+                    path: PathBuf::from(String::from_utf8_lossy(path).into_owned()),
+                    old: blob(old),
+                    new: blob(new),
+                });
+            }
+        } else if !field.is_empty() {
+            commit = field.to_string();
+        }
+    }
+    entries
+}
+
+/// Whether blob contents look binary (a NUL byte near the start), like git's own check
+pub fn is_binary_blob(data: &[u8]) -> bool {
+    data.iter().take(8000).any(|&b| b == 0)
+}
+
+/// Reads blob contents through one long-running `git cat-file --batch` process
+pub struct BlobReader {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl BlobReader {
+    fn new(root: &Path) -> RfgrepResult<Self> {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| RfgrepError::Other(format!("Failed to run git: {e}")))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    /// Contents of the object `name` (a blob id or `commit:path`), or `None` if it
+    /// does not exist
+    pub fn read(&mut self, name: &str) -> RfgrepResult<Option<Vec<u8>>> {
+        self.read_if(name, |_| true)
+    }
+
+    /// Like [`read`](Self::read), but only keeps the contents if `accept` agrees given
+    /// their size in bytes; otherwise they are skipped over and `None` is returned
+    pub fn read_if(
+        &mut self,
+        name: &str,
+        accept: impl FnOnce(u64) -> bool,
+    ) -> RfgrepResult<Option<Vec<u8>>> {
+        writeln!(self.stdin, "{name}").map_err(RfgrepError::Io)?;
+        self.stdin.flush().map_err(RfgrepError::Io)?;
+
+        let mut header = String::new();
+        self.stdout
+            .read_line(&mut header)
+            .map_err(RfgrepError::Io)?;
+        let header = header.trim_end();
+        if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+            return Ok(None);
+        }
+        let size: usize = header
+            .rsplit(' ')
+            .next()
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(|| {
+                RfgrepError::Other(format!("Unexpected git cat-file output: {header}"))
+            })?;
+
+        // The contents are followed by a newline
+        if !accept(size as u64) {
+            std::io::copy(
+                &mut (&mut self.stdout).take(size as u64 + 1),
+                &mut std::io::sink(),
+            )
+            .map_err(RfgrepError::Io)?;
+            return Ok(None);
+        }
+        let mut data = vec![0; size + 1];
+        self.stdout.read_exact(&mut data).map_err(RfgrepError::Io)?;
+        data.truncate(size);
+        Ok(Some(data))
+    }
+}
+
+impl Drop for BlobReader {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Like [`git`], decoding the output as text
+fn git_text(root: &Path, args: &[&str]) -> RfgrepResult<String> {
+    git(root, args).map(|out| String::from_utf8_lossy(&out).into_owned())
+}
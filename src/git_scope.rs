@@ -0,0 +1,119 @@
+//! Git-aware file selection for `--git-changed`, `--git-staged`, `--git-untracked`
+//! and `--git-tracked-only`.
+//!
+//! The file set comes from the local repository through the `git` command line
+//! (no network access), so only the selected files are stat'ed and searched
+//! instead of walking the whole tree.
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::walker::split_path_list;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Which files of the repository to search. The changed, staged and untracked sets are
+/// combined; `tracked` narrows them to tracked files, or selects every tracked file alone.
+#[derive(Debug, Clone, Default)]
+pub struct GitScope {
+    /// Files that differ from this ref in the index or working tree
+    pub changed: Option<String>,
+    /// Files with staged changes
+    pub staged: bool,
+    /// Untracked files that are not ignored
+    pub untracked: bool,
+    /// Only tracked files
+    pub tracked: bool,
+}
+
+impl GitScope {
+    /// Whether no git option was given, so the normal walk should be used
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_none() && !self.staged && !self.untracked && !self.tracked
+    }
+
+    /// Resolve the selected files under `root`, sorted and without duplicates.
+    /// Deleted files are left out.
+    pub fn files(&self, root: &Path) -> RfgrepResult<Vec<PathBuf>> {
+        if !root.is_dir() {
+            return Err(RfgrepError::Other(format!(
+                "Git options need a directory inside a repository, got {}",
+                root.display()
+            )));
+        }
+
+        let mut listings = Vec::new();
+        if let Some(reference) = &self.changed {
+            listings.push(git(
+                root,
+                &[
+                    "diff",
+                    "--name-only",
+                    "-z",
+                    "--relative",
+                    "--diff-filter=ACMRT",
+                    reference,
+                    "--",
+                ],
+            )?);
+        }
+        if self.staged {
+            listings.push(git(
+                root,
+                &[
+                    "diff",
+                    "--cached",
+                    "--name-only",
+                    "-z",
+                    "--relative",
+                    "--diff-filter=ACMRT",
+                ],
+            )?);
+        }
+        if self.untracked {
+            listings.push(git(
+                root,
+                &["ls-files", "--others", "--exclude-standard", "-z"],
+            )?);
+        }
+
+        let mut selected = (!listings.is_empty()).then(|| listed_paths(root, &listings));
+        if self.tracked {
+            let tracked = listed_paths(root, &[git(root, &["ls-files", "--cached", "-z"])?]);
+            selected = Some(match selected {
+                Some(selected) => selected.intersection(&tracked).cloned().collect(),
+                None => tracked,
+            });
+        }
+        Ok(selected
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect())
+    }
+}
+
+/// Paths from NUL-separated `git` listings, joined to `root`
+fn listed_paths(root: &Path, listings: &[Vec<u8>]) -> BTreeSet<PathBuf> {
+    listings
+        .iter()
+        .flat_map(|listing| split_path_list(listing, true))
+        .map(|path| root.join(path))
+        .collect()
+}
+
+/// Run `git -C root args...` and return its standard output
+pub(crate) fn git(root: &Path, args: &[&str]) -> RfgrepResult<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .map_err(|e| RfgrepError::Other(format!("Failed to run git: {e}")))?;
+    if !output.status.success() {
+        return Err(RfgrepError::Other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
@@ -4,6 +4,7 @@ use crate::error::{Result as RfgrepResult, RfgrepError};
 use crate::metrics::Metrics;
 use crate::search::algorithms::*;
 use crate::search::SearchEngine;
+use crate::walker::{walk_dir_with, WalkOptions};
 use colored::Colorize;
 use std::collections::VecDeque;
 use std::path::Path;
@@ -15,6 +16,7 @@ pub struct InteractiveEngine {
     search_engine: SearchEngine,
     history: VecDeque<String>,
     max_history: usize,
+    walk_options: WalkOptions,
 }
 
 /// Interactive search state
@@ -37,9 +39,16 @@ impl InteractiveEngine {
             search_engine,
             history: VecDeque::new(),
             max_history: 100,
+            walk_options: WalkOptions::default(),
         })
     }
 
+    /// Apply hidden-file, ignore-file and glob settings when discovering files
+    pub fn with_walk_options(mut self, walk_options: WalkOptions) -> Self {
+        self.walk_options = walk_options;
+        self
+    }
+
     /// Run interactive search
     pub async fn run(
         &mut self,
@@ -86,12 +95,15 @@ impl InteractiveEngine {
         recursive: bool,
         extensions: Option<&[String]>,
     ) -> RfgrepResult<Vec<std::path::PathBuf>> {
-        use crate::walker::walk_dir;
-
-        let files: Vec<_> = walk_dir(root_path, recursive, true)
-            .filter(|entry| entry.path().is_file())
-            .map(|entry| entry.path().to_path_buf())
-            .collect();
+        let files: Vec<_> = walk_dir_with(
+            root_path,
+            recursive,
+            &self.walk_options.or_defaults(true, false),
+        )?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
 
         // Apply extension filter
         let filtered_files: Vec<_> = if let Some(extensions) = extensions {
@@ -408,7 +420,7 @@ impl InteractiveEngine {
             }
 
             let mut ext_vec: Vec<_> = ext_counts.into_iter().collect();
-            ext_vec.sort_by(|a, b| b.1.cmp(&a.1));
+            ext_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
             if !ext_vec.is_empty() {
                 println!("\nMatches by file type:");
@@ -502,6 +514,8 @@ impl SearchAlgorithmTrait for RegexSearch {
                     column_start: match_pos - text[..match_pos].rfind('\n').unwrap_or(0),
                     column_end: match_pos - text[..match_pos].rfind('\n').unwrap_or(0)
                         + matched_text.len(),
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -0,0 +1,211 @@
+//! Language detection for files whose extension does not say what they are.
+//!
+//! Well-known file names (`Jenkinsfile`, `Gemfile`, `Dockerfile.dev`), shebang lines
+//! (`#!/usr/bin/env python3`) and editor modelines (`# vim: ft=ruby`,
+//! `-*- mode: sh -*-`) are mapped to the named types of `--type`, so classification
+//! and type filters treat an extensionless script like any other source file.
+use crate::file_types::DEFAULT_TYPES;
+use std::io::Read;
+use std::path::Path;
+
+/// How much of a file is read to look for a shebang or modeline
+const SNIFF_BYTES: usize = 4096;
+
+/// Number of lines at the start and end of a file that may hold a modeline, as in Vim
+const MODELINE_LINES: usize = 5;
+
+/// Named type of `path` from its file name or, for files without an extension, from a
+/// shebang or modeline in the first few kilobytes
+pub fn detect_file_type(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    if let Some(detected) = detect_by_name(name) {
+        return Some(detected);
+    }
+    if path.extension().is_some() {
+        return None;
+    }
+
+    let mut sample = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)
+        .ok()?
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut sample)
+        .ok()?;
+    detect_by_content(&sample)
+}
+
+/// Named type for a well-known file name such as `Makefile` or `Dockerfile.dev`,
+/// from the name globs of the built-in types
+pub fn detect_by_name(name: &str) -> Option<&'static str> {
+    DEFAULT_TYPES.iter().find_map(|(type_name, globs)| {
+        globs
+            .iter()
+            .filter(|glob| !glob.starts_with('*'))
+            .any(|glob| match glob.strip_suffix(".*") {
+                Some(stem) => name
+                    .strip_prefix(stem)
+                    .is_some_and(|rest| rest.starts_with('.')),
+                None => name == *glob,
+            })
+            .then_some(*type_name)
+    })
+}
+
+/// Named type from the start of a file: a shebang on the first line, or a Vim or Emacs
+/// modeline in the first or last few lines of `sample`
+pub fn detect_by_content(sample: &[u8]) -> Option<&'static str> {
+    if sample.contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(sample);
+    let lines: Vec<&str> = text.lines().collect();
+
+    if let Some(detected) = lines.first().and_then(|line| shebang_type(line)) {
+        return Some(detected);
+    }
+    let tail_start = lines
+        .len()
+        .saturating_sub(MODELINE_LINES)
+        .max(MODELINE_LINES);
+    lines
+        .iter()
+        .take(MODELINE_LINES)
+        .chain(lines.iter().skip(tail_start))
+        .find_map(|line| modeline_type(line))
+}
+
+/// `#!/usr/bin/env python3`, `#!/bin/sh -e`, `#!/usr/bin/env -S node --flag`
+fn shebang_type(line: &str) -> Option<&'static str> {
+    let command = line.strip_prefix("#!")?;
+    let mut words = command.split_whitespace();
+    let mut interpreter = basename(words.next()?);
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-'))?;
+    }
+    language_type(basename(interpreter))
+}
+
+/// `vim: set ft=python:`, `vi: filetype=sh`, `-*- mode: ruby -*-`, `-*- python -*-`
+fn modeline_type(line: &str) -> Option<&'static str> {
+    if let Some(start) = line.find("-*-") {
+        let rest = &line[start + 3..];
+        let variables = &rest[..rest.find("-*-")?];
+        if !variables.contains(':') {
+            return language_type(variables.trim());
+        }
+        return variables.split(';').find_map(|variable| {
+            let (key, value) = variable.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("mode")
+                .then(|| language_type(value.trim()))?
+        });
+    }
+
+    let start = ["vim:", "vi:", "ex:"]
+        .iter()
+        .filter_map(|marker| line.find(marker).map(|at| at + marker.len()))
+        .min()?;
+    line[start..]
+        .split(|c: char| c == ':' || c.is_whitespace())
+        .find_map(|setting| {
+            let value = setting
+                .strip_prefix("ft=")
+                .or_else(|| setting.strip_prefix("filetype="))
+                .or_else(|| setting.strip_prefix("syntax="))?;
+            language_type(value)
+        })
+}
+
+fn basename(command: &str) -> &str {
+    command.rsplit('/').next().unwrap_or(command)
+}
+
+/// Map an interpreter or editor language name to a named type
+fn language_type(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    // Versioned interpreters: python3.12, ruby2.7, perl5
+    let name = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    Some(match name {
+        "python" | "pypy" => "python",
+        "sh" | "bash" | "zsh" | "dash" | "ksh" | "mksh" | "ash" | "fish" | "shell-script" => {
+            "shell"
+        }
+        "ruby" | "jruby" => "ruby",
+        "node" | "nodejs" | "deno" | "bun" | "javascript" | "js" => "js",
+        "ts-node" | "tsx" | "typescript" => "ts",
+        "perl" | "cperl" => "perl",
+        "php" => "php",
+        "lua" | "luajit" => "lua",
+        "rscript" | "r" => "r",
+        "pwsh" | "powershell" | "ps1" => "powershell",
+        "groovy" | "jenkinsfile" => "groovy",
+        "make" | "makefile" | "gmake" => "make",
+        "dockerfile" => "docker",
+        "rust" => "rust",
+        "go" => "go",
+        "java" => "java",
+        "kotlin" => "kotlin",
+        "scala" => "scala",
+        "swift" => "swift",
+        "c" => "c",
+        "cpp" | "c++" => "cpp",
+        "yaml" => "yaml",
+        "json" => "json",
+        "toml" | "conf-toml" => "toml",
+        "markdown" | "md" => "markdown",
+        "html" => "html",
+        "css" => "css",
+        "sql" => "sql",
+        "xml" | "nxml" => "xml",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_by_name() {
+        assert_eq!(detect_by_name("Jenkinsfile"), Some("groovy"));
+        assert_eq!(detect_by_name("Gemfile"), Some("ruby"));
+        assert_eq!(detect_by_name("Dockerfile.dev"), Some("docker"));
+        assert_eq!(detect_by_name("Makefile"), Some("make"));
+        assert_eq!(detect_by_name("Dockerfiles"), None);
+        assert_eq!(detect_by_name("main.rs"), None);
+    }
+
+    #[test]
+    fn test_detect_shebang() {
+        assert_eq!(
+            detect_by_content(b"#!/usr/bin/env python3\nprint(1)\n"),
+            Some("python")
+        );
+        assert_eq!(detect_by_content(b"#!/bin/bash -e\n"), Some("shell"));
+        assert_eq!(
+            detect_by_content(b"#!/usr/bin/env -S node --no-warnings\n"),
+            Some("js")
+        );
+        assert_eq!(detect_by_content(b"#!/usr/bin/perl -w\n"), Some("perl"));
+        assert_eq!(detect_by_content(b"#!/opt/unknown\n"), None);
+    }
+
+    #[test]
+    fn test_detect_modeline() {
+        assert_eq!(
+            detect_by_content(b"# vim: set ft=ruby:\nputs 1\n"),
+            Some("ruby")
+        );
+        assert_eq!(
+            detect_by_content(b"# -*- mode: sh; indent-tabs-mode: nil -*-\n"),
+            Some("shell")
+        );
+        assert_eq!(detect_by_content(b"; -*- python -*-\n"), Some("python"));
+
+        let mut tail = "x = 1\n".repeat(20);
+        tail.push_str("# vi: filetype=python\n");
+        assert_eq!(detect_by_content(tail.as_bytes()), Some("python"));
+        assert_eq!(detect_by_content(b"just some text\n"), None);
+        assert_eq!(detect_by_content(b"#!/bin/sh\0\0"), None);
+    }
+}
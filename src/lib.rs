@@ -89,6 +89,15 @@
 /// Simplified application architecture with async runtime support
 pub mod app_simple;
 
+/// Byte-level search helpers: hex patterns and hexdumps
+pub mod binary;
+
+/// Resource budgets: byte, file, memory and time limits for a search run
+pub mod budget;
+
+/// Cooperative cancellation for Ctrl-C handling
+pub mod cancel;
+
 /// Command-line interface definitions and argument parsing
 pub mod cli;
 
@@ -98,9 +107,21 @@ mod config;
 /// Error types and result handling
 pub mod error;
 
+/// Time, owner and permission predicates applied during the walk
+pub mod file_filters;
+
 /// File type classification system supporting 153+ formats
 pub mod file_types;
 
+/// Searching git revisions and history through a blob reader
+pub mod git_history;
+
+/// Git-aware file selection (changed, staged, untracked, tracked)
+pub mod git_scope;
+
+/// Language detection from well-known file names, shebangs and modelines
+pub mod language_detection;
+
 /// Test utilities and benchmarking tools (enabled for test/bench/examples features)
 #[cfg(any(test, feature = "bench", feature = "examples"))]
 pub mod test_utils;
@@ -141,6 +162,9 @@ pub mod processor;
 /// Progress tracking and reporting
 mod progress;
 
+/// Named searches saved per user or shared through a project file
+pub mod saved_searches;
+
 /// Search algorithm implementations
 mod search;
 
@@ -150,6 +174,9 @@ pub mod search_algorithms;
 /// Streaming search pipeline for large files
 pub mod streaming_search;
 
+/// End-of-run search summary and grep-compatible exit status
+pub mod summary;
+
 /// Interactive Terminal User Interface
 pub mod tui;
 
@@ -185,7 +212,7 @@ use std::path::Path;
 pub use std::path::PathBuf;
 
 /// Directory walking functionality
-pub use walker::walk_dir;
+pub use walker::{walk_dir, walk_dir_parallel, walk_dir_with, walk_roots, WalkOptions};
 /// Application configuration for rfgrep operations
 ///
 /// Contains runtime configuration including chunk sizes, executable paths,
@@ -234,12 +261,9 @@ impl AppConfig {
 /// # Returns
 /// * `AppConfig` - Loaded or default configuration
 pub fn load_config() -> AppConfig {
-    let mut cfg = Config::default();
-    if let Ok(config) = Config::load() {
-        cfg = config;
-    }
+    let cfg = Config::load(None).unwrap_or_default();
     AppConfig {
-        chunk_size: Some(cfg.search.chunk_size as u32),
+        chunk_size: Some(cfg.search.chunk_size.unwrap_or(100) as u32),
         rfgrep_exe: std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("rfgrep")),
         results_dir: std::path::PathBuf::from("results"),
     }
@@ -1,7 +1,7 @@
 //! File listing engine with advanced filtering and statistics
 use crate::error::{Result as RfgrepResult, RfgrepError};
 use crate::metrics::Metrics;
-use crate::walker::walk_dir;
+use crate::walker::{walk_dir_with, WalkOptions};
 use colored::*;
 use std::path::Path;
 use std::sync::Arc;
@@ -19,12 +19,22 @@ pub struct FileInfo {
 /// List engine for file operations
 pub struct ListEngine {
     metrics: Arc<Metrics>,
+    walk_options: WalkOptions,
 }
 
 impl ListEngine {
     /// Create a new list engine
     pub fn new(metrics: Arc<Metrics>) -> RfgrepResult<Self> {
-        Ok(Self { metrics })
+        Ok(Self {
+            metrics,
+            walk_options: WalkOptions::default(),
+        })
+    }
+
+    /// Apply hidden-file, ignore-file and glob settings when walking the tree
+    pub fn with_walk_options(mut self, walk_options: WalkOptions) -> Self {
+        self.walk_options = walk_options;
+        self
     }
 
     /// List files with various filters and options
@@ -40,7 +50,10 @@ impl ListEngine {
         reverse: bool,
         limit: Option<usize>,
     ) -> RfgrepResult<Vec<FileInfo>> {
-        let entries: Vec<_> = walk_dir(root_path, recursive, show_hidden).collect();
+        let walk = self.walk_options.or_defaults(show_hidden, !show_hidden);
+        let entries: Vec<_> = walk_dir_with(root_path, recursive, &walk)?
+            .filter_map(Result::ok)
+            .collect();
 
         // Process files in parallel
         use rayon::prelude::*;
@@ -157,7 +170,7 @@ impl ListEngine {
                 files.sort_by(|a, b| a.path.cmp(&b.path));
             }
             crate::cli::SortCriteria::Size => {
-                files.sort_by(|a, b| a.size.cmp(&b.size));
+                files.sort_by_key(|a| a.size);
             }
             crate::cli::SortCriteria::Date => {
                 files.sort_by(|a, b| match (&a.modified, &b.modified) {
@@ -234,7 +247,7 @@ impl ListEngine {
         }
 
         let mut ext_vec: Vec<_> = ext_counts.into_iter().collect();
-        ext_vec.sort_by(|a, b| b.1.cmp(&a.1));
+        ext_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
         println!("\n{}", "Statistics:".green().bold());
         println!("{}: {}", "Total files".cyan(), files.len());
@@ -13,53 +13,64 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::print_literal)]
 mod app_simple;
+mod binary;
+mod budget;
+mod cancel;
 mod cli;
 mod config;
 mod error;
+mod file_filters;
 mod file_types;
+mod git_history;
+mod git_scope;
+mod language_detection;
 mod memory;
 mod output_formats;
 mod plugin_cli;
 mod plugin_system;
 mod processor;
+mod saved_searches;
 mod search_algorithms;
 mod streaming_search;
+mod summary;
 mod tui;
 mod walker;
 
+use crate::cancel::CancellationToken;
 use crate::error::{Result as RfgrepResult, RfgrepError};
-use clap::Parser;
 use cli::*;
 use env_logger::{Builder, Env, Target};
 use std::fs;
+use std::process::ExitCode;
 use std::time::Instant;
-
-#[cfg(unix)]
-use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
-
-#[cfg(unix)]
-static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
-
-fn main() {
-    let result = main_inner();
-    if let Err(e) = result {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
+use summary::ExitStatus;
+
+fn main() -> ExitCode {
+    match main_inner() {
+        Ok(status) => status.into(),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitStatus::Error.into()
+        }
     }
 }
 
-fn main_inner() -> RfgrepResult<()> {
-    #[cfg(unix)]
+fn main_inner() -> RfgrepResult<ExitStatus> {
+    // First Ctrl-C stops the run gracefully; a second one exits immediately
+    let cancel = CancellationToken::new();
     {
-        let shutdown_flag = &SHUTDOWN_REQUESTED;
+        let cancel = cancel.clone();
         ctrlc::set_handler(move || {
-            shutdown_flag.store(true, AtomicOrdering::SeqCst);
+            if cancel.is_cancelled() {
+                std::process::exit(ExitStatus::Interrupted.code().into());
+            }
+            cancel.cancel();
             eprintln!("\nShutdown requested, finishing current operations...");
         })
         .expect("Failed to set Ctrl-C handler");
     }
 
-    let cli = Cli::parse();
+    let cli = config::parse_cli(std::env::args_os())?;
     // setup_logging(&cli)?;
 
     let start_time = Instant::now();
@@ -69,7 +80,7 @@ fn main_inner() -> RfgrepResult<()> {
 
     let suppress_verbose = cli.quiet
         || is_piped
-        || matches!(&cli.command, Commands::Search { output_format, ndjson, .. } if output_format == &cli::OutputFormat::Json || *ndjson);
+        || matches!(&cli.command, Commands::Search { output_format, ndjson, .. } if output_format.is_structured() || *ndjson);
 
     let verbose = cli.verbose;
 
@@ -78,8 +89,10 @@ fn main_inner() -> RfgrepResult<()> {
     }
 
     let rt = tokio::runtime::Runtime::new()?;
-    rt.block_on(async {
-        let app = app_simple::RfgrepApp::new_async().await?;
+    let status = rt.block_on(async {
+        let app = app_simple::RfgrepApp::new_async()
+            .await?
+            .with_cancellation(cancel);
         app.run(cli).await
     })?;
 
@@ -89,7 +102,7 @@ fn main_inner() -> RfgrepResult<()> {
             start_time.elapsed()
         );
     }
-    Ok(())
+    Ok(status)
 }
 
 fn setup_logging(cli: &Cli) -> RfgrepResult<()> {
@@ -82,6 +82,17 @@ impl AdaptiveMemoryManager {
         }
     }
 
+    /// Record a resident memory sample, keeping the peak up to date
+    pub fn record_resident_memory(&self, bytes: u64) {
+        self.current_memory_usage.store(bytes, Ordering::Relaxed);
+        self.peak_memory_usage.fetch_max(bytes, Ordering::Relaxed);
+    }
+
+    /// Whether `bytes` exceeds `max_memory_usage_mb`
+    pub fn exceeds_limit(&self, bytes: u64) -> bool {
+        bytes > self.config.max_memory_usage_mb * 1024 * 1024
+    }
+
     #[allow(dead_code)]
     fn get_available_memory(&self) -> u64 {
         8 * 1024 * 1024 * 1024
@@ -99,6 +110,22 @@ impl AdaptiveMemoryManager {
     }
 }
 
+/// Resident set size of this process in bytes, where the platform reports it
+pub fn resident_memory() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        // SAFETY: sysconf only reads a system constant
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        Some(pages * u64::try_from(page_size).ok()?)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
 /// Memory usage statistics
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -1,6 +1,12 @@
+use crate::binary::{hex_bytes, BinaryMatch};
 use crate::processor::SearchMatch;
 use serde_json::{json, Value};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// JSON schema of the SARIF 2.1.0 log format
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// URI base of relative artifact locations in SARIF output
+const SARIF_SRCROOT: &str = "%SRCROOT%";
 
 /// Output format types
 #[derive(Debug, Clone)]
@@ -13,6 +19,7 @@ pub enum OutputFormat {
     Markdown,
     Csv,
     Tsv,
+    Sarif,
 }
 
 /// Default implementation for OutputFormat
@@ -30,6 +37,9 @@ pub struct OutputFormatter {
     include_context: bool,
     use_color: bool,
     ndjson: bool,
+    summary: Option<Value>,
+    arguments: Vec<String>,
+    working_directory: Option<PathBuf>,
 }
 
 impl Default for OutputFormatter {
@@ -40,6 +50,9 @@ impl Default for OutputFormatter {
             include_context: true,
             use_color: is_terminal::is_terminal(&std::io::stdout()),
             ndjson: false,
+            summary: None,
+            arguments: Vec::new(),
+            working_directory: None,
         }
     }
 }
@@ -53,6 +66,9 @@ impl OutputFormatter {
             include_context: true,
             use_color: is_terminal::is_terminal(&std::io::stdout()),
             ndjson: false,
+            summary: None,
+            arguments: Vec::new(),
+            working_directory: None,
         }
     }
 
@@ -62,6 +78,26 @@ impl OutputFormatter {
         self
     }
 
+    /// Attach a run summary, emitted as a `summary` object in JSON output and as
+    /// a final `{"summary": ...}` line in NDJSON
+    #[allow(dead_code)]
+    pub fn with_summary(mut self, summary: Value) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
+    /// Record the command line and working directory of the run, written to the
+    /// `invocations` of SARIF output
+    pub fn with_invocation(
+        mut self,
+        arguments: Vec<String>,
+        working_directory: Option<PathBuf>,
+    ) -> Self {
+        self.arguments = arguments;
+        self.working_directory = working_directory;
+        self
+    }
+
     #[allow(dead_code)]
     pub fn with_color(mut self, use_color: bool) -> Self {
         self.use_color = use_color;
@@ -91,68 +127,56 @@ impl OutputFormatter {
             OutputFormat::Markdown => self.format_markdown(matches, query, path),
             OutputFormat::Csv => self.format_csv(matches, query, path),
             OutputFormat::Tsv => self.format_tsv(matches, query, path),
+            OutputFormat::Sarif => self.format_sarif(matches, query, path),
         }
     }
 
+    /// Whether results can be written incrementally, one file at a time. Only the
+    /// line-oriented formats (text and NDJSON) stream; the others are documents.
+    pub fn supports_streaming(&self) -> bool {
+        self.ndjson || matches!(self.format, OutputFormat::Text)
+    }
+
+    /// Header written before the first streamed batch
+    pub fn format_stream_header(&self, query: &str, path: &Path) -> String {
+        if self.ndjson || !self.include_metadata {
+            return String::new();
+        }
+        format!("Query: {query}\nPath: {}\n\n", path.display())
+    }
+
+    /// Format one file's matches for incremental output
+    pub fn format_stream_batch(&self, matches: &[SearchMatch], query: &str) -> String {
+        if self.ndjson {
+            self.format_ndjson_matches(matches, query)
+        } else {
+            self.format_text_matches(matches)
+        }
+    }
+
+    /// Footer written after the last streamed batch: the match total for text, the
+    /// `{"summary": ...}` line for NDJSON
+    pub fn format_stream_footer(&self, total_matches: usize) -> String {
+        if self.ndjson {
+            return self
+                .summary
+                .as_ref()
+                .map(ndjson_summary_line)
+                .unwrap_or_default();
+        }
+        if !self.include_metadata {
+            return String::new();
+        }
+        format!("\nTotal matches: {total_matches}\n")
+    }
+
     /// Format as JSON
     #[allow(dead_code)]
     fn format_json(&self, matches: &[SearchMatch], query: &str, path: &Path) -> String {
         if self.ndjson {
-            let mut out = String::new();
-            for m in matches {
-                let mut match_obj = json!({
-                    "query": query,
-                    "path": m.path.to_string_lossy(),
-                    "line_number": m.line_number,
-                    "line": m.line,
-                    "matched_text": m.matched_text,
-                    "column_start": m.column_start,
-                    "column_end": m.column_end,
-                });
-
-                if self.include_context {
-                    let context_before: Vec<Value> = m
-                        .context_before
-                        .iter()
-                        .map(|(num, line)| {
-                            json!({
-                                "line_number": num,
-                                "content": line
-                            })
-                        })
-                        .collect();
-
-                    let context_after: Vec<Value> = m
-                        .context_after
-                        .iter()
-                        .map(|(num, line)| {
-                            json!({
-                                "line_number": num,
-                                "content": line
-                            })
-                        })
-                        .collect();
-
-                    match_obj["context_before"] = Value::Array(context_before);
-                    match_obj["context_after"] = Value::Array(context_after);
-                }
-
-                match serde_json::to_string(&match_obj) {
-                    Ok(s) => {
-                        out.push_str(&s);
-                        out.push('\n');
-                    }
-                    Err(e) => {
-                        let err_obj =
-                            json!({"error": "json_serialization_failed", "details": e.to_string()});
-                        if let Ok(s) = serde_json::to_string(&err_obj) {
-                            out.push_str(&s);
-                            out.push('\n');
-                        } else {
-                            out.push_str("{\"error\":\"json_serialization_failed\"}\n");
-                        }
-                    }
-                }
+            let mut out = self.format_ndjson_matches(matches, query);
+            if let Some(summary) = &self.summary {
+                out.push_str(&ndjson_summary_line(summary));
             }
             return out;
         }
@@ -203,14 +227,93 @@ impl OutputFormatter {
                 match_obj["context_after"] = Value::Array(context_after);
             }
 
+            if let Some(binary) = &m.binary {
+                match_obj["binary"] = binary_json(binary);
+            }
+            if let Some(revision) = &m.revision {
+                match_obj["revision"] = json!(revision);
+            }
+
             matches_array.push(match_obj);
         }
 
+        if let Some(summary) = &self.summary {
+            result["summary"] = summary.clone();
+        }
+
         serde_json::to_string(&result).unwrap_or_else(|e| {
             format!(r#"{{"error":"json_serialization_failed","details":"{e}"}}"#)
         })
     }
 
+    /// One JSON object per match, newline-terminated
+    fn format_ndjson_matches(&self, matches: &[SearchMatch], query: &str) -> String {
+        let mut out = String::new();
+        for m in matches {
+            let mut match_obj = json!({
+                "query": query,
+                "path": m.path.to_string_lossy(),
+                "line_number": m.line_number,
+                "line": m.line,
+                "matched_text": m.matched_text,
+                "column_start": m.column_start,
+                "column_end": m.column_end,
+            });
+
+            if self.include_context {
+                let context_before: Vec<Value> = m
+                    .context_before
+                    .iter()
+                    .map(|(num, line)| {
+                        json!({
+                            "line_number": num,
+                            "content": line
+                        })
+                    })
+                    .collect();
+
+                let context_after: Vec<Value> = m
+                    .context_after
+                    .iter()
+                    .map(|(num, line)| {
+                        json!({
+                            "line_number": num,
+                            "content": line
+                        })
+                    })
+                    .collect();
+
+                match_obj["context_before"] = Value::Array(context_before);
+                match_obj["context_after"] = Value::Array(context_after);
+            }
+
+            if let Some(binary) = &m.binary {
+                match_obj["binary"] = binary_json(binary);
+            }
+            if let Some(revision) = &m.revision {
+                match_obj["revision"] = json!(revision);
+            }
+
+            match serde_json::to_string(&match_obj) {
+                Ok(s) => {
+                    out.push_str(&s);
+                    out.push('\n');
+                }
+                Err(e) => {
+                    let err_obj =
+                        json!({"error": "json_serialization_failed", "details": e.to_string()});
+                    if let Ok(s) = serde_json::to_string(&err_obj) {
+                        out.push_str(&s);
+                        out.push('\n');
+                    } else {
+                        out.push_str("{\"error\":\"json_serialization_failed\"}\n");
+                    }
+                }
+            }
+        }
+        out
+    }
+
     /// Format as plain text (default)
     #[allow(dead_code)]
     fn format_text(&self, matches: &[SearchMatch], query: &str, path: &Path) -> String {
@@ -222,8 +325,19 @@ impl OutputFormatter {
             output.push_str(&format!("Total matches: {}\n\n", matches.len()));
         }
 
-        // default one-line-per-match: path:line:col: line-with-highlight
+        output.push_str(&self.format_text_matches(matches));
+        output
+    }
+
+    /// Default one-line-per-match text body: `path:line:col: line-with-highlight`
+    fn format_text_matches(&self, matches: &[SearchMatch]) -> String {
+        let mut output = String::new();
         for m in matches {
+            if let Some(binary) = &m.binary {
+                output.push_str(&self.format_binary_text(m, binary));
+                continue;
+            }
+
             let line_len = m.line.len();
             let column_start = m.column_start.min(line_len);
             let column_end = m.column_end.min(line_len);
@@ -276,6 +390,78 @@ impl OutputFormatter {
         output
     }
 
+    /// Format a binary match as `path:0xOFFSET: bytes` followed by an xxd-style dump
+    fn format_binary_text(&self, m: &SearchMatch, binary: &BinaryMatch) -> String {
+        if binary.encoding.is_some() {
+            return self.format_string_text(m, binary);
+        }
+
+        let mut output = String::new();
+        let bytes = hex_bytes(&binary.bytes);
+        let bytes = if self.use_color {
+            format!("\x1b[33m{bytes}\x1b[0m")
+        } else {
+            bytes
+        };
+        output.push_str(&format!(
+            "{}:0x{:08x}: {bytes}\n",
+            m.path.display(),
+            binary.offset
+        ));
+
+        if self.include_context {
+            output.push_str("-- hexdump --\n");
+            for row in binary.hexdump() {
+                output.push_str(&format!("  {row}\n"));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Format a match in an extracted string as `path:0xOFFSET: string`, with
+    /// neighbouring strings as context
+    fn format_string_text(&self, m: &SearchMatch, binary: &BinaryMatch) -> String {
+        let mut output = String::new();
+        let line_len = m.line.len();
+        let column_start = m.column_start.min(line_len);
+        let column_end = m.column_end.min(line_len).max(column_start);
+        let before = &m.line[..column_start];
+        let matched = &m.line[column_start..column_end];
+        let after = &m.line[column_end..];
+        let matched = if self.use_color {
+            format!("\x1b[33m{matched}\x1b[0m")
+        } else {
+            matched.to_string()
+        };
+
+        output.push_str(&format!(
+            "{}:0x{:08x}: {before}{matched}{after}\n",
+            m.path.display(),
+            binary.offset
+        ));
+
+        if self.include_context
+            && (!binary.strings_before.is_empty() || !binary.strings_after.is_empty())
+        {
+            output.push_str("-- context --\n");
+            for (offset, line) in &binary.strings_before {
+                output.push_str(&format!("  0x{offset:08x} │ {line}\n"));
+            }
+            output.push_str(&format!(
+                "→ 0x{:08x} │ {before}{matched}{after}\n",
+                binary.offset
+            ));
+            for (offset, line) in &binary.strings_after {
+                output.push_str(&format!("  0x{offset:08x} │ {line}\n"));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
     /// Format as XML
     #[allow(dead_code)]
     fn format_xml(&self, matches: &[SearchMatch], query: &str, path: &Path) -> String {
@@ -491,6 +677,233 @@ impl OutputFormatter {
 
         output
     }
+
+    /// Format as a SARIF 2.1.0 log for code-scanning tools: the pattern is the run's
+    /// rule and every match a result located by line and column, or by byte offset
+    /// for binary matches
+    #[allow(dead_code)]
+    fn format_sarif(&self, matches: &[SearchMatch], query: &str, _path: &Path) -> String {
+        let rule = json!({
+            "id": query,
+            "name": "PatternMatch",
+            "shortDescription": { "text": format!("Matches of `{query}`") },
+            "defaultConfiguration": { "level": "note" },
+        });
+        let results: Vec<Value> = matches
+            .iter()
+            .map(|m| self.sarif_result(m, query))
+            .collect();
+
+        let mut run = json!({
+            "tool": {
+                "driver": {
+                    "name": "rfgrep",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "semanticVersion": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": [rule],
+                }
+            },
+            "invocations": [self.sarif_invocation()],
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        });
+        if let Some(dir) = &self.working_directory {
+            run["originalUriBaseIds"] = json!({ SARIF_SRCROOT: { "uri": directory_uri(dir) } });
+        }
+
+        let log = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [run],
+        });
+        match serde_json::to_string_pretty(&log) {
+            Ok(s) => format!("{s}\n"),
+            Err(e) => format!(r#"{{"error":"sarif_serialization_failed","details":"{e}"}}"#),
+        }
+    }
+
+    /// One SARIF result; columns count Unicode code points from 1 and the end
+    /// column is exclusive
+    fn sarif_result(&self, m: &SearchMatch, query: &str) -> Value {
+        let path = m
+            .revision
+            .as_ref()
+            .map_or(m.path.as_path(), |revision| revision.path.as_path());
+        let mut location = json!({ "artifactLocation": artifact_location(path) });
+
+        if let Some(binary) = &m.binary {
+            location["region"] = json!({
+                "byteOffset": binary.offset,
+                "byteLength": binary.bytes.len(),
+            });
+        } else {
+            location["region"] = json!({
+                "startLine": m.line_number,
+                "startColumn": code_point_column(&m.line, m.column_start),
+                "endLine": m.line_number,
+                "endColumn": code_point_column(&m.line, m.column_end),
+                "snippet": { "text": m.line },
+            });
+            if self.include_context && (!m.context_before.is_empty() || !m.context_after.is_empty())
+            {
+                let start_line = m.context_before.first().map_or(m.line_number, |(n, _)| *n);
+                let end_line = m.context_after.last().map_or(m.line_number, |(n, _)| *n);
+                let mut text = String::new();
+                for (_, line) in &m.context_before {
+                    text.push_str(line);
+                    text.push('\n');
+                }
+                text.push_str(&m.line);
+                text.push('\n');
+                for (_, line) in &m.context_after {
+                    text.push_str(line);
+                    text.push('\n');
+                }
+                location["contextRegion"] = json!({
+                    "startLine": start_line,
+                    "endLine": end_line,
+                    "snippet": { "text": text },
+                });
+            }
+        }
+
+        let mut result = json!({
+            "ruleId": query,
+            "ruleIndex": 0,
+            "level": "note",
+            "message": { "text": format!("Found `{}`", m.matched_text) },
+            "locations": [{ "physicalLocation": location }],
+        });
+        if let Some(revision) = &m.revision {
+            result["properties"] = json!({ "revision": revision });
+        }
+        result
+    }
+
+    /// The run's command line and working directory, with the summary's issues as
+    /// notifications. A partial run is reported as unsuccessful.
+    fn sarif_invocation(&self) -> Value {
+        let partial = self
+            .summary
+            .as_ref()
+            .and_then(|summary| summary["partial"].as_bool())
+            .unwrap_or(false);
+        let mut invocation = json!({ "executionSuccessful": !partial });
+
+        if let Some((_, arguments)) = self.arguments.split_first() {
+            invocation["commandLine"] = json!(self.arguments.join(" "));
+            invocation["arguments"] = json!(arguments);
+        }
+        if let Some(dir) = &self.working_directory {
+            invocation["workingDirectory"] = json!({ "uri": directory_uri(dir) });
+        }
+        if let Some(summary) = &self.summary {
+            let notifications: Vec<Value> = summary["issues"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|issue| {
+                    let level = match issue["kind"].as_str() {
+                        Some("error") => "error",
+                        Some("timeout") => "warning",
+                        _ => "note",
+                    };
+                    let path = Path::new(issue["path"].as_str().unwrap_or_default());
+                    json!({
+                        "level": level,
+                        "message": { "text": issue["reason"] },
+                        "locations": [{
+                            "physicalLocation": { "artifactLocation": artifact_location(path) }
+                        }],
+                    })
+                })
+                .collect();
+            if !notifications.is_empty() {
+                invocation["toolExecutionNotifications"] = json!(notifications);
+            }
+            invocation["properties"] = json!({ "summary": summary });
+        }
+        invocation
+    }
+}
+
+/// Final NDJSON line carrying the run summary
+fn ndjson_summary_line(summary: &Value) -> String {
+    format!("{}\n", json!({ "summary": summary }))
+}
+
+/// JSON representation of a binary match: offset, matched bytes and hexdump rows,
+/// plus the encoding for matches in extracted strings
+fn binary_json(binary: &BinaryMatch) -> Value {
+    let mut value = json!({
+        "offset": binary.offset,
+        "length": binary.bytes.len(),
+        "bytes": hex_bytes(&binary.bytes),
+    });
+    if !binary.context.is_empty() {
+        value["hexdump"] = json!(binary.hexdump());
+    }
+    if let Some(encoding) = binary.encoding {
+        value["encoding"] = json!(encoding.to_string());
+    }
+    let strings = |strings: &[(u64, String)]| -> Vec<Value> {
+        strings
+            .iter()
+            .map(|(offset, content)| json!({ "offset": offset, "content": content }))
+            .collect()
+    };
+    if !binary.strings_before.is_empty() {
+        value["strings_before"] = json!(strings(&binary.strings_before));
+    }
+    if !binary.strings_after.is_empty() {
+        value["strings_after"] = json!(strings(&binary.strings_after));
+    }
+    value
+}
+
+/// SARIF artifact location: a `file://` URI for absolute paths, otherwise a path
+/// relative to the working directory (`%SRCROOT%`)
+fn artifact_location(path: &Path) -> Value {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    let uri = percent_encode(&path.to_string_lossy().replace('\\', "/"));
+    if path.is_absolute() {
+        let uri = uri.trim_start_matches('/');
+        json!({ "uri": format!("file:///{uri}") })
+    } else {
+        json!({ "uri": uri, "uriBaseId": SARIF_SRCROOT })
+    }
+}
+
+/// `file://` URI of a directory, ending in `/` so relative URIs resolve inside it
+fn directory_uri(dir: &Path) -> String {
+    let uri = artifact_location(dir)["uri"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    if uri.ends_with('/') {
+        uri
+    } else {
+        format!("{uri}/")
+    }
+}
+
+/// Percent-encode everything but unreserved characters and the path separators
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+/// 1-based column, in Unicode code points, of the byte offset `byte` in `line`
+fn code_point_column(line: &str, byte: usize) -> usize {
+    line.char_indices().take_while(|(i, _)| *i < byte).count() + 1
 }
 
 /// Escape CSV special characters
@@ -530,3 +943,69 @@ fn escape_html(s: &str) -> String {
         .replace("\"", "&quot;")
         .replace("'", "&#39;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_match(path: &str, line: &str, needle: &str) -> SearchMatch {
+        let column_start = line.find(needle).unwrap();
+        SearchMatch {
+            path: PathBuf::from(path),
+            line_number: 2,
+            line: line.to_string(),
+            context_before: vec![(1, "fn main() {".to_string())],
+            context_after: vec![(3, "}".to_string())],
+            matched_text: needle.to_string(),
+            column_start,
+            column_end: column_start + needle.len(),
+            binary: None,
+            revision: None,
+        }
+    }
+
+    #[test]
+    fn test_sarif_result_regions() {
+        let formatter = OutputFormatter::new(OutputFormat::Sarif)
+            .with_invocation(
+                vec!["rfgrep".to_string(), "search".to_string()],
+                Some(PathBuf::from("/work/my project")),
+            )
+            .with_summary(json!({ "partial": true, "issues": [] }));
+        let matches = [search_match(
+            "./src/my file.rs",
+            "    let café = needle;",
+            "needle",
+        )];
+        let log: Value =
+            serde_json::from_str(&formatter.format_results(&matches, "needle", Path::new(".")))
+                .unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "needle");
+        assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+        assert_eq!(
+            run["originalUriBaseIds"][SARIF_SRCROOT]["uri"],
+            "file:///work/my%20project/"
+        );
+
+        let location = &run["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/my%20file.rs");
+        assert_eq!(location["region"]["startColumn"], 16);
+        assert_eq!(location["region"]["endColumn"], 22);
+        assert_eq!(location["contextRegion"]["startLine"], 1);
+        assert_eq!(
+            location["contextRegion"]["snippet"]["text"],
+            "fn main() {\n    let café = needle;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_absolute_paths_are_file_uris() {
+        assert_eq!(
+            artifact_location(Path::new("/tmp/a#b.rs")),
+            json!({ "uri": "file:///tmp/a%23b.rs" })
+        );
+    }
+}
@@ -261,6 +261,8 @@ pub fn to_owned_matches<'a>(
             line: m.matched_text.to_string(), // This should be the full line content
             column_start: m.column,
             column_end: m.column + m.matched_text.len(),
+            binary: None,
+            revision: None,
             matched_text: m.matched_text.to_string(),
             context_before: m
                 .context_before
@@ -1,5 +1,6 @@
 //! Enhanced plugin system for rfgrep with dynamic loading and better integration
-use crate::error::Result as RfgrepResult;
+use crate::binary::{hex_bytes, hexdump, BinaryMatch, HexPattern, HEXDUMP_ROW_BYTES};
+use crate::error::{Result as RfgrepResult, RfgrepError};
 use crate::processor::SearchMatch;
 use crate::search_algorithms::SearchAlgorithm;
 use crate::streaming_search::{StreamingConfig, StreamingSearchPipeline};
@@ -376,6 +377,8 @@ impl EnhancedSearchPlugin for EnhancedTextSearchPlugin {
                     matched_text: mat.as_str().to_string(),
                     column_start: mat.start(),
                     column_end: mat.end(),
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -440,6 +443,7 @@ pub struct EnhancedBinarySearchPlugin {
     config: PluginConfig,
     binary_extensions: Vec<String>,
     search_metadata: bool,
+    hexdump_rows: usize,
 }
 
 impl EnhancedBinarySearchPlugin {
@@ -460,8 +464,58 @@ impl EnhancedBinarySearchPlugin {
                 "map".to_string(),
             ],
             search_metadata: true,
+            hexdump_rows: 1,
         }
     }
+
+    /// Set the number of hexdump rows captured on each side of a match
+    pub fn with_hexdump_rows(mut self, rows: usize) -> Self {
+        self.hexdump_rows = rows;
+        self
+    }
+
+    /// Search a file for a byte pattern, reporting absolute offsets
+    pub fn search_hex(&self, file: &Path, pattern: &HexPattern) -> RfgrepResult<Vec<SearchMatch>> {
+        let handle = std::fs::File::open(file)?;
+        if handle.metadata()?.len() == 0 {
+            return Ok(vec![]);
+        }
+        let data = unsafe { memmap2::Mmap::map(&handle) }.map_err(RfgrepError::Mmap)?;
+        Ok(self.search_hex_in(&data, file, pattern))
+    }
+
+    /// Search in-memory bytes for a byte pattern, reporting matches under `path`
+    pub fn search_hex_in(
+        &self,
+        data: &[u8],
+        path: &Path,
+        pattern: &HexPattern,
+    ) -> Vec<SearchMatch> {
+        pattern
+            .find_all(data)
+            .into_iter()
+            .map(|offset| {
+                let binary = BinaryMatch::capture(data, offset, pattern.len(), self.hexdump_rows);
+                let row_start = offset - offset % HEXDUMP_ROW_BYTES;
+                let row_end = (row_start + HEXDUMP_ROW_BYTES).min(data.len());
+                SearchMatch {
+                    path: path.to_path_buf(),
+                    line_number: 0,
+                    line: hexdump(row_start as u64, &data[row_start..row_end])
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default(),
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    matched_text: hex_bytes(&binary.bytes),
+                    column_start: offset,
+                    column_end: offset + binary.bytes.len(),
+                    binary: Some(binary),
+                    revision: None,
+                }
+            })
+            .collect()
+    }
 }
 
 impl EnhancedSearchPlugin for EnhancedBinarySearchPlugin {
@@ -497,28 +551,7 @@ impl EnhancedSearchPlugin for EnhancedBinarySearchPlugin {
     }
 
     fn search(&self, file: &Path, pattern: &str) -> RfgrepResult<Vec<SearchMatch>> {
-        let content = std::fs::read(file)?;
-        let pattern_bytes = pattern.as_bytes();
-
-        let mut matches = Vec::new();
-        let mut pos = 0;
-
-        while let Some(found) = memchr::memmem::find(&content[pos..], pattern_bytes) {
-            let absolute_pos = pos + found;
-            matches.push(SearchMatch {
-                path: file.to_path_buf(),
-                line_number: 1,
-                line: format!("Binary data at offset {}", absolute_pos),
-                context_before: Vec::new(),
-                context_after: Vec::new(),
-                matched_text: pattern.to_string(),
-                column_start: absolute_pos,
-                column_end: absolute_pos + pattern_bytes.len(),
-            });
-            pos = absolute_pos + 1;
-        }
-
-        Ok(matches)
+        self.search_hex(file, &HexPattern::from_literal(pattern.as_bytes()))
     }
 
     fn supported_extensions(&self) -> Vec<String> {
@@ -536,6 +569,15 @@ impl EnhancedSearchPlugin for EnhancedBinarySearchPlugin {
                 value_type: ConfigValueType::Boolean,
             },
         );
+        options.insert(
+            "hexdump_rows".to_string(),
+            PluginConfigOption {
+                name: "Hexdump Rows".to_string(),
+                description: "Hexdump rows of context shown around each match".to_string(),
+                default_value: serde_json::Value::Number(serde_json::Number::from(1)),
+                value_type: ConfigValueType::Integer,
+            },
+        );
         options
     }
 
@@ -545,6 +587,11 @@ impl EnhancedSearchPlugin for EnhancedBinarySearchPlugin {
                 self.search_metadata = bool_val;
             }
         }
+        if let Some(value) = config.get("hexdump_rows") {
+            if let Some(int_val) = value.as_u64() {
+                self.hexdump_rows = int_val as usize;
+            }
+        }
         Ok(())
     }
 
@@ -715,6 +762,22 @@ mod tests {
         assert!(stats.total_plugins > 0);
     }
 
+    #[test]
+    fn test_binary_plugin_hex_search() {
+        let plugin = EnhancedBinarySearchPlugin::new();
+        let mut data = vec![0u8; 48];
+        data[20..24].copy_from_slice(&[0xde, 0xad, 0x42, 0xef]);
+        let pattern = HexPattern::parse("DE AD ?? EF").unwrap();
+
+        let matches = plugin.search_hex_in(&data, Path::new("firmware.bin"), &pattern);
+        assert_eq!(matches.len(), 1);
+        let binary = matches[0].binary.as_ref().unwrap();
+        assert_eq!(binary.offset, 20);
+        assert_eq!(binary.bytes, vec![0xde, 0xad, 0x42, 0xef]);
+        assert_eq!(binary.context_offset, 0);
+        assert_eq!(matches[0].matched_text, "de ad 42 ef");
+    }
+
     #[tokio::test]
     async fn test_text_plugin() {
         let _plugin = EnhancedTextSearchPlugin::new();
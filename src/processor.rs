@@ -1,7 +1,10 @@
 // processor.rs
 //! File-level search helpers and match extraction used by the rfgrep core.
+use crate::binary::BinaryMatch;
 use crate::error::{Result as RfgrepResult, RfgrepError};
 use crate::file_types::{FileTypeClassifier, SearchDecision};
+use crate::git_history::RevisionMatch;
+use crate::summary::SkipReason;
 use lazy_static::lazy_static;
 use log::{debug, info, warn};
 use memmap2::Mmap;
@@ -55,6 +58,12 @@ pub struct SearchMatch {
     pub matched_text: String,
     pub column_start: usize,
     pub column_end: usize,
+    /// Byte offset and hexdump context for matches found in binary data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary: Option<BinaryMatch>,
+    /// Commit and path for matches read from git history (`--rev`, `--history`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<RevisionMatch>,
 }
 
 lazy_static! {
@@ -136,16 +145,40 @@ pub fn is_binary(file: &Path) -> bool {
 
 /// Decide whether a file should be skipped entirely before attempting to read/scan it.
 /// Uses smart file type classification with extension, MIME, and size analysis.
-pub fn should_skip(path: &Path, metadata: &Metadata) -> bool {
+pub fn should_skip(path: &Path, metadata: &Metadata, follow: bool) -> bool {
+    skip_reason(path, metadata, follow).is_some()
+}
+
+/// Why a file should be skipped entirely, as a category and a description, or `None`
+/// when it should be searched. When `metadata` describes a symbolic link, the link is
+/// skipped unless `follow` is set, in which case its target is classified instead.
+pub fn skip_reason(path: &Path, metadata: &Metadata, follow: bool) -> Option<(SkipReason, String)> {
+    let target;
+    let metadata = if metadata.file_type().is_symlink() {
+        if !follow {
+            debug!("Skipping symlink without --follow: {}", path.display());
+            return Some((SkipReason::SpecialFile, "symlink".to_string()));
+        }
+        match path.metadata() {
+            Ok(metadata) => {
+                target = metadata;
+                &target
+            }
+            Err(_) => return Some((SkipReason::Unreadable, "broken symlink".to_string())),
+        }
+    } else {
+        metadata
+    };
+
     if metadata.is_dir() {
-        return true;
+        return Some((SkipReason::SpecialFile, "directory".to_string()));
     }
 
     if let Ok(s) = path.canonicalize() {
         if let Some(root_str) = s.to_str() {
             if root_str.starts_with("/proc") || root_str.starts_with("/dev") {
                 debug!("Skipping kernel fs path: {}", path.display());
-                return true;
+                return Some((SkipReason::SpecialFile, "kernel file system".to_string()));
             }
         }
     }
@@ -154,33 +187,24 @@ pub fn should_skip(path: &Path, metadata: &Metadata) -> bool {
     #[cfg(unix)]
     {
         use std::os::unix::fs::FileTypeExt;
-        if ftype.is_socket()
-            || ftype.is_fifo()
-            || ftype.is_block_device()
-            || ftype.is_char_device()
-            || ftype.is_symlink()
+        if ftype.is_socket() || ftype.is_fifo() || ftype.is_block_device() || ftype.is_char_device()
         {
             debug!("Skipping special unix file type: {}", path.display());
-            return true;
-        }
-    }
-    #[cfg(windows)]
-    {
-        if ftype.is_symlink() {
-            debug!("Skipping symlink on windows: {}", path.display());
-            return true;
+            return Some((SkipReason::SpecialFile, "special file type".to_string()));
         }
     }
 
     let classifier = FileTypeClassifier::new();
-    match classifier.should_search(path, metadata) {
+    let explanation = classifier.explain(path, metadata);
+    match explanation.decision {
         SearchDecision::Search(_) => {
             debug!("Searching file: {}", path.display());
-            false
+            None
         }
         SearchDecision::Skip(reason) => {
             debug!("Skipping file: {} - {}", path.display(), reason);
-            true
+            let skip_reason = explanation.skip_reason.unwrap_or(SkipReason::UnknownType);
+            Some((skip_reason, reason))
         }
         SearchDecision::Conditional(mode, reason) => {
             debug!(
@@ -189,7 +213,7 @@ pub fn should_skip(path: &Path, metadata: &Metadata) -> bool {
                 reason,
                 mode
             );
-            false
+            None
         }
     }
 }
@@ -216,15 +240,30 @@ pub fn get_or_compile_regex(pattern: &str) -> RfgrepResult<Regex> {
     }
 }
 
+/// Search `path` for `pattern`, following it if it is a symbolic link
 pub fn search_file(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch>> {
+    search_file_with(path, pattern, true)
+}
+
+/// Like [`search_file`], but a symbolic link is skipped unless `follow` is set
+pub fn search_file_with(
+    path: &Path,
+    pattern: &Regex,
+    follow: bool,
+) -> RfgrepResult<Vec<SearchMatch>> {
     let _start = Instant::now();
     let file_display = path.display();
     debug!("Starting search in file: {file_display}");
     let file = File::open(path).map_err(RfgrepError::Io)?;
-    let metadata = file.metadata().map_err(RfgrepError::Io)?;
+    let metadata = if follow {
+        file.metadata()
+    } else {
+        path.symlink_metadata()
+    }
+    .map_err(RfgrepError::Io)?;
     let file_size = metadata.len();
 
-    if should_skip(path, &metadata) {
+    if should_skip(path, &metadata, follow) {
         info!("Skipping file by pre-scan heuristic: {file_display}");
         return Ok(vec![]);
     }
@@ -294,6 +333,8 @@ fn find_matches_with_context(
                 matched_text: m.as_str().to_string(),
                 column_start: m.start(),
                 column_end: m.end(),
+                binary: None,
+                revision: None,
             });
         }
     }
@@ -334,6 +375,8 @@ fn find_matches_streaming<R: Read>(
                 matched_text: m.as_str().to_string(),
                 column_start: m.start(),
                 column_end: m.end(),
+                binary: None,
+                revision: None,
             });
         }
     }
@@ -0,0 +1,277 @@
+//! Saved searches: named `rfgrep search` argument lists.
+//!
+//! Searches are stored in `saved.toml` next to the user config
+//! (`$XDG_CONFIG_HOME/rfgrep/saved.toml`) and in project-local
+//! `.rfgrep-saved.toml` files meant to be committed and shared. Like config files,
+//! project files are read from the filesystem root down to the current directory
+//! and a closer file replaces a search of the same name.
+//!
+//! ```toml
+//! [searches.todo-owners]
+//! args = ["TODO\\((\\w+)\\)", "--mode", "regex", "--algorithm", "regex", "--", "src"]
+//! description = "TODOs with an owner"
+//! ```
+use crate::cli::Cli;
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project-local saved searches file
+pub const PROJECT_SAVED_FILE: &str = ".rfgrep-saved.toml";
+
+/// One saved search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedSearch {
+    /// Arguments following `rfgrep search`
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl SavedSearch {
+    /// Check that `args` form a valid `rfgrep search` command line
+    pub fn new(args: Vec<String>, description: Option<String>) -> RfgrepResult<Self> {
+        let search = Self { args, description };
+        search.parse()?;
+        Ok(search)
+    }
+
+    /// The full command line, starting with `rfgrep search`
+    pub fn command_line(&self) -> Vec<String> {
+        ["rfgrep", "search"]
+            .into_iter()
+            .map(String::from)
+            .chain(self.args.iter().cloned())
+            .collect()
+    }
+
+    /// Parse the arguments as an `rfgrep search` command
+    pub fn parse(&self) -> RfgrepResult<Cli> {
+        Cli::try_parse_from(self.command_line()).map_err(|e| {
+            RfgrepError::Config(format!(
+                "Invalid search arguments: {}",
+                e.to_string().trim()
+            ))
+        })
+    }
+
+    /// The command line quoted for a POSIX shell
+    pub fn display(&self) -> String {
+        self.command_line()
+            .iter()
+            .map(|arg| shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Where a saved search is defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SavedSource {
+    User(PathBuf),
+    Project(PathBuf),
+}
+
+impl SavedSource {
+    pub fn path(&self) -> &Path {
+        match self {
+            SavedSource::User(path) | SavedSource::Project(path) => path,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SavedFile {
+    #[serde(default)]
+    searches: BTreeMap<String, SavedSearch>,
+}
+
+impl SavedFile {
+    fn read(path: &Path) -> RfgrepResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(RfgrepError::Io)?;
+        toml::from_str(&content).map_err(|e| {
+            RfgrepError::Config(format!(
+                "Invalid saved searches file {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    fn write(&self, path: &Path) -> RfgrepResult<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| RfgrepError::Other(format!("Failed to serialize saved searches: {e}")))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(RfgrepError::Io)?;
+        }
+        fs::write(path, content).map_err(RfgrepError::Io)
+    }
+}
+
+/// Every saved search that applies in a directory, by name
+#[derive(Debug, Default, Clone)]
+pub struct SavedSearches {
+    searches: BTreeMap<String, (SavedSearch, SavedSource)>,
+}
+
+impl SavedSearches {
+    /// Load the user file, then the project files from the filesystem root down to `dir`
+    pub fn load(dir: &Path) -> RfgrepResult<Self> {
+        let mut sources: Vec<SavedSource> = user_saved_path()
+            .ok()
+            .into_iter()
+            .map(SavedSource::User)
+            .collect();
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let mut project: Vec<SavedSource> = dir
+            .ancestors()
+            .map(|ancestor| SavedSource::Project(ancestor.join(PROJECT_SAVED_FILE)))
+            .collect();
+        project.reverse();
+        sources.extend(project);
+
+        let mut saved = Self::default();
+        for source in sources {
+            for (name, search) in SavedFile::read(source.path())?.searches {
+                saved.searches.insert(name, (search, source.clone()));
+            }
+        }
+        Ok(saved)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedSearch> {
+        self.searches.get(name).map(|(search, _)| search)
+    }
+
+    /// The search called `name`, or an error listing the known names
+    pub fn find(&self, name: &str) -> RfgrepResult<&SavedSearch> {
+        self.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.searches.keys().map(String::as_str).collect();
+            RfgrepError::Config(if known.is_empty() {
+                format!("No saved search named '{name}'; add one with `rfgrep saved add`")
+            } else {
+                format!(
+                    "No saved search named '{name}' (saved: {})",
+                    known.join(", ")
+                )
+            })
+        })
+    }
+
+    pub fn source(&self, name: &str) -> Option<&SavedSource> {
+        self.searches.get(name).map(|(_, source)| source)
+    }
+
+    /// Saved searches in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &SavedSearch, &SavedSource)> {
+        self.searches
+            .iter()
+            .map(|(name, (search, source))| (name.as_str(), search, source))
+    }
+}
+
+/// Store `search` as `name` in the file at `path`; an existing search of that name
+/// is only replaced with `force`
+pub fn save_search(path: &Path, name: &str, search: SavedSearch, force: bool) -> RfgrepResult<()> {
+    let mut file = SavedFile::read(path)?;
+    if file.searches.contains_key(name) && !force {
+        return Err(RfgrepError::Config(format!(
+            "A search named '{name}' is already saved in {}; use --force to replace it",
+            path.display()
+        )));
+    }
+    file.searches.insert(name.to_string(), search);
+    file.write(path)
+}
+
+/// Remove `name` from the file at `path`, returning whether it was there
+pub fn remove_search(path: &Path, name: &str) -> RfgrepResult<bool> {
+    let mut file = SavedFile::read(path)?;
+    let removed = file.searches.remove(name).is_some();
+    if removed {
+        file.write(path)?;
+    }
+    Ok(removed)
+}
+
+/// The user's saved searches file, next to the user config
+pub fn user_saved_path() -> RfgrepResult<PathBuf> {
+    crate::config::user_config_path().map(|config| config.with_file_name("saved.toml"))
+}
+
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_saved_search_round_trip_and_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(project.join("src")).unwrap();
+
+        let outer = SavedSearch::new(args(&["TODO", "--recursive"]), None).unwrap();
+        let inner = SavedSearch::new(
+            args(&["FIXME", "--", "src"]),
+            Some("Fix me later".to_string()),
+        )
+        .unwrap();
+        save_search(
+            &temp_dir.path().join(PROJECT_SAVED_FILE),
+            "todo",
+            outer.clone(),
+            false,
+        )
+        .unwrap();
+        let inner_file = project.join(PROJECT_SAVED_FILE);
+        save_search(&inner_file, "todo", inner.clone(), false).unwrap();
+        assert!(save_search(&inner_file, "todo", outer.clone(), false).is_err());
+
+        let saved = SavedSearches::load(&project.join("src")).unwrap();
+        assert_eq!(saved.get("todo"), Some(&inner));
+        assert_eq!(
+            saved.source("todo").map(SavedSource::path),
+            Some(inner_file.canonicalize().unwrap().as_path())
+        );
+
+        assert!(remove_search(&inner_file, "todo").unwrap());
+        let saved = SavedSearches::load(&project).unwrap();
+        assert_eq!(saved.get("todo"), Some(&outer));
+        assert!(saved.find("nothing").is_err());
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_rejected() {
+        assert!(SavedSearch::new(args(&["TODO", "--no-such-flag"]), None).is_err());
+        assert!(SavedSearch::new(vec![], None).is_err());
+    }
+
+    #[test]
+    fn test_display_quotes_for_the_shell() {
+        let search = SavedSearch::new(args(&["fn main()", "--", "it's"]), None).unwrap();
+        assert_eq!(search.display(), r"rfgrep search 'fn main()' -- 'it'\''s'");
+    }
+}
@@ -90,6 +90,8 @@ impl SearchAlgorithmTrait for SimdSearch {
                     matched_text,
                     column_start,
                     column_end,
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -190,6 +192,8 @@ impl SearchAlgorithmTrait for AhoCorasickSearch {
                     column_start: match_pos - text[..match_pos].rfind('\n').unwrap_or(0),
                     column_end: match_pos - text[..match_pos].rfind('\n').unwrap_or(0)
                         + matched_text.len(),
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -333,6 +337,8 @@ impl SearchAlgorithmTrait for FuzzySearch {
                     matched_text,
                     column_start,
                     column_end,
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -494,6 +500,8 @@ impl SearchAlgorithmTrait for RabinKarpSearch {
                     matched_text,
                     column_start,
                     column_end,
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -143,6 +143,8 @@ impl SearchPlugin for TextSearchPlugin {
                     matched_text: mat.as_str().to_string(),
                     column_start: mat.start(),
                     column_end: mat.end(),
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -216,6 +218,8 @@ impl SearchPlugin for BinarySearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                binary: None,
+                revision: None,
             });
             pos = absolute_pos + 1;
         }
@@ -286,6 +290,8 @@ impl SearchPlugin for ArchiveSearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                binary: None,
+                revision: None,
             });
             pos = absolute_pos + 1;
         }
@@ -352,6 +358,8 @@ impl SearchPlugin for DatabaseSearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                binary: None,
+                revision: None,
             });
             pos = absolute_pos + 1;
         }
@@ -421,6 +429,8 @@ impl SearchPlugin for ImageSearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                binary: None,
+                revision: None,
             });
             pos = absolute_pos + 1;
         }
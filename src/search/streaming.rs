@@ -114,6 +114,8 @@ impl StreamingSearch {
                                 matched_text: pattern.to_string(),
                                 column_start: match_pos,
                                 column_end: match_pos + pattern.len(),
+                                binary: None,
+                                revision: None,
                             });
                         }
                     }
@@ -146,6 +148,8 @@ impl StreamingSearch {
                     matched_text: pattern.to_string(),
                     column_start: match_pos,
                     column_end: match_pos + pattern.len(),
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -329,6 +329,13 @@ pub trait SearchAlgorithmTrait: Send + Sync {
         context_lines: usize,
     ) -> Vec<SearchMatch>;
 
+    /// Length in bytes of the match that `search` found at `pos` in `text`; the
+    /// pattern's length for literal searches
+    fn match_len(&self, text: &str, pos: usize, pattern: &str) -> usize {
+        let _ = (text, pos);
+        pattern.len()
+    }
+
     fn get_context_before(
         &self,
         lines: &[&str],
@@ -569,4 +576,11 @@ impl SearchAlgorithmTrait for RegexSearch {
     ) -> Vec<SearchMatch> {
         self.search_with_context(text, pattern, context_lines)
     }
+
+    fn match_len(&self, text: &str, pos: usize, _pattern: &str) -> usize {
+        self.regex
+            .find_at(text, pos)
+            .filter(|m| m.start() == pos)
+            .map_or(0, |m| m.len())
+    }
 }
@@ -1,11 +1,16 @@
 //! Streaming search pipeline for efficient file processing
+use crate::binary::{extract_strings, BinaryMatch};
+use crate::budget::BudgetTracker;
+use crate::cancel::CancellationToken;
 use crate::error::{Result as RfgrepResult, RfgrepError};
 use crate::processor::SearchMatch as ProcessorSearchMatch;
 use crate::search_algorithms::{SearchAlgorithm, SearchAlgorithmTrait, SearchMatch};
+use crate::summary::{SearchSummary, SkipReason};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::task;
 
@@ -20,6 +25,8 @@ pub struct StreamingConfig {
     pub timeout_per_file: Option<u64>,
     pub chunk_size: usize,
     pub buffer_size: usize,
+    /// Search printable strings of at least this length extracted from binary data
+    pub binary_strings: Option<usize>,
 }
 
 impl Default for StreamingConfig {
@@ -33,18 +40,100 @@ impl Default for StreamingConfig {
             timeout_per_file: None,
             chunk_size: 8192,   // 8KB chunks
             buffer_size: 65536, // 64KB buffer
+            binary_strings: None,
         }
     }
 }
 
 /// Streaming search pipeline
+#[derive(Clone)]
 pub struct StreamingSearchPipeline {
     config: StreamingConfig,
+    summary: Option<Arc<SearchSummary>>,
+    deadline: Option<Instant>,
+    cancel: Option<CancellationToken>,
+    budget: Option<Arc<BudgetTracker>>,
 }
 
+/// Lines between deadline and cancellation checks while scanning a file
+const DEADLINE_CHECK_INTERVAL: usize = 256;
+
 impl StreamingSearchPipeline {
     pub fn new(config: StreamingConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            summary: None,
+            deadline: None,
+            cancel: None,
+            budget: None,
+        }
+    }
+
+    /// Record per-file errors, timeouts and skips into `summary` instead of
+    /// printing them
+    pub fn with_summary(mut self, summary: Arc<SearchSummary>) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
+    /// Stop searching at `deadline`, keeping the matches found so far.
+    ///
+    /// Files started after the deadline are recorded as not reached and files
+    /// interrupted mid-scan as timed out, so the summary marks the run as partial.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    fn deadline_reached(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Stop starting new files and abandon the current one once `cancel` fires,
+    /// keeping the matches found so far
+    pub fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.is_cancelled())
+    }
+
+    /// Charge every file against `budget` before searching it; once a limit is
+    /// reached no further files are started and the summary names the limit.
+    ///
+    /// The budget's time limit is not enforced here: fold it into the deadline.
+    pub fn with_budget(mut self, budget: Arc<BudgetTracker>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Whether the budget is used up, recording the limit in the summary if so
+    fn budget_exhausted(&self, path: &Path, size: Option<u64>) -> bool {
+        let Some(budget) = &self.budget else {
+            return false;
+        };
+        let charged = match size {
+            Some(size) => budget.charge(size),
+            None => budget.time_exceeded().map_or(Ok(()), Err),
+        };
+        match (charged, &self.summary) {
+            (Err(limit), Some(summary)) => summary.budget_reached(path, limit),
+            (Err(_), None) => {}
+            (Ok(()), _) => return false,
+        }
+        true
+    }
+
+    /// Attribute a deadline stop to the time budget when that is what ran out
+    fn note_time_budget(&self, summary: &SearchSummary) {
+        if let Some(limit) = self.budget.as_ref().and_then(|b| b.time_exceeded()) {
+            summary.mark_budget_exceeded(limit);
+        }
     }
 
     /// Search a single file using streaming approach
@@ -53,67 +142,200 @@ impl StreamingSearchPipeline {
         path: &Path,
         pattern: &str,
     ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
-        // Early binary check
-        if crate::processor::is_binary(path) {
+        if self.cancelled() || self.budget_exhausted(path, None) {
             return Ok(vec![]);
         }
 
-        // Helper future that performs the actual search
-        let do_search = async {
-            let file = File::open(path).map_err(RfgrepError::Io)?;
-            let reader = BufReader::with_capacity(self.config.buffer_size, file);
-
-            // Create search algorithm instance
-            let search_algo = self.create_search_algorithm(pattern)?;
+        if self.deadline_reached() {
+            if let Some(summary) = &self.summary {
+                summary.not_reached(path, "deadline reached before search started");
+            }
+            return Ok(vec![]);
+        }
 
-            // Process file in chunks
-            let matches = self
-                .process_file_streaming(reader, search_algo.as_ref(), pattern, path)
-                .await?;
+        // Early binary check; like file selection, only listed with --show-skipped
+        if self.config.binary_strings.is_none() && crate::processor::is_binary(path) {
+            if let Some(summary) = &self.summary {
+                summary.filtered(path, SkipReason::Binary, "binary file");
+            }
+            return Ok(vec![]);
+        }
 
-            // Apply post-processing
-            let mut final_matches = self.apply_post_processing(matches, path)?;
+        let size = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+        if self.budget_exhausted(path, Some(size)) {
+            return Ok(vec![]);
+        }
 
-            // Apply max_matches limit
-            if let Some(max_matches) = self.config.max_matches {
-                if final_matches.len() > max_matches {
-                    final_matches.truncate(max_matches);
-                }
+        // Helper future that performs the actual search
+        let do_search = async {
+            let file = File::open(path).map_err(RfgrepError::Io)?;
+            if self.config.binary_strings.is_some() {
+                let mut data = Vec::new();
+                BufReader::with_capacity(self.config.buffer_size, file)
+                    .read_to_end(&mut data)
+                    .map_err(RfgrepError::Io)?;
+                return self.search_strings_in(&data, pattern, path).await;
             }
+            self.search_reader(file, pattern, path).await
+        };
 
-            RfgrepResult::Ok(final_matches)
+        let per_file = self.config.timeout_per_file.map(Duration::from_secs);
+        let remaining = self
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        let limit = match (per_file, remaining) {
+            (Some(per_file), Some(remaining)) => Some(per_file.min(remaining)),
+            (per_file, remaining) => per_file.or(remaining),
         };
 
-        if let Some(timeout_secs) = self.config.timeout_per_file {
+        let result = if let Some(limit) = limit {
             // If test env variable is set, simulate work taking time
-            if let Ok(s) = std::env::var("RFGREP_WORKER_SLEEP") {
-                if let Ok(sec) = s.parse::<u64>() {
+            let simulated_sleep = std::env::var("RFGREP_WORKER_SLEEP")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok());
+            let timed = async {
+                if let Some(sec) = simulated_sleep {
                     // Sleep inside the timed section to simulate long-running work
-                    let sleep_fut = async {
-                        tokio::time::sleep(std::time::Duration::from_secs(sec)).await;
-                        do_search.await
-                    };
-                    return match tokio::time::timeout(
-                        std::time::Duration::from_secs(timeout_secs),
-                        sleep_fut,
-                    )
-                    .await
-                    {
-                        Ok(res) => res,
-                        Err(_elapsed) => Ok(vec![]),
-                    };
+                    tokio::time::sleep(Duration::from_secs(sec)).await;
                 }
-            }
-            // Enforce per-file timeout: on timeout, return no matches
-            match tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), do_search)
-                .await
-            {
+                do_search.await
+            };
+            // Enforce the per-file timeout and run deadline: on timeout, return no matches
+            match tokio::time::timeout(limit, timed).await {
                 Ok(res) => res,
-                Err(_elapsed) => Ok(vec![]),
+                Err(_elapsed) => {
+                    if let Some(summary) = &self.summary {
+                        match self.config.timeout_per_file {
+                            Some(secs) if per_file == Some(limit) => summary.timeout(path, secs),
+                            _ => {
+                                self.note_time_budget(summary);
+                                summary.stopped_at_deadline(path, None)
+                            }
+                        }
+                    }
+                    return Ok(vec![]);
+                }
             }
         } else {
             do_search.await
+        };
+
+        if let (Some(summary), Err(e)) = (&self.summary, &result) {
+            summary.error(path, e);
+        }
+        result
+    }
+
+    /// Search contents already read into memory, such as a git blob, reporting matches
+    /// under `path`. Like [`search_file`](Self::search_file), the contents are charged
+    /// to the budget and nothing is searched once the run deadline has passed.
+    pub async fn search_stored(
+        &self,
+        data: Vec<u8>,
+        pattern: &str,
+        path: &Path,
+    ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
+        if self.cancelled() || self.budget_exhausted(path, Some(data.len() as u64)) {
+            return Ok(vec![]);
+        }
+        if self.deadline_reached() {
+            if let Some(summary) = &self.summary {
+                summary.not_reached(path, "deadline reached before search started");
+            }
+            return Ok(vec![]);
+        }
+        self.search_reader(std::io::Cursor::new(data), pattern, path)
+            .await
+    }
+
+    /// Search an arbitrary reader (e.g. standard input), reporting matches under `path`
+    pub async fn search_reader<R: Read + Send + 'static>(
+        &self,
+        reader: R,
+        pattern: &str,
+        path: &Path,
+    ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
+        let reader = BufReader::with_capacity(self.config.buffer_size, reader);
+
+        // Create search algorithm instance
+        let search_algo = self.create_search_algorithm(pattern)?;
+
+        // Process input in chunks
+        let (matches, stopped_at) = self
+            .process_file_streaming(reader, search_algo.as_ref(), pattern, path)
+            .await?;
+
+        if let Some(summary) = &self.summary {
+            match stopped_at {
+                // An interrupted run is reported as a whole, not per file
+                Some(_) if self.cancelled() => {}
+                Some(line_number) => {
+                    self.note_time_budget(summary);
+                    summary.stopped_at_deadline(path, Some(line_number))
+                }
+                None => summary.file_searched(),
+            }
+        }
+
+        // Apply post-processing
+        let mut final_matches = self.apply_post_processing(matches, path)?;
+
+        // Apply max_matches limit
+        if let Some(max_matches) = self.config.max_matches {
+            if final_matches.len() > max_matches {
+                final_matches.truncate(max_matches);
+            }
         }
+
+        Ok(final_matches)
+    }
+
+    /// Search the printable strings embedded in binary data (like `strings -a -e l`).
+    ///
+    /// Each match carries the byte offset and encoding of its string; context entries
+    /// are neighbouring strings keyed by their offsets.
+    pub async fn search_strings_in(
+        &self,
+        data: &[u8],
+        pattern: &str,
+        path: &Path,
+    ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
+        let min_len = self.config.binary_strings.unwrap_or(4);
+        let strings = extract_strings(data, min_len);
+
+        let mut text = String::new();
+        for extracted in &strings {
+            text.push_str(&extracted.text);
+            text.push('\n');
+        }
+
+        // Matches were found on the "lines" of the string list; report byte offsets in
+        // the binary instead, leaving the line fields empty as for hex matches
+        let with_offsets = |context: Vec<(usize, String)>| -> Vec<(u64, String)> {
+            context
+                .into_iter()
+                .filter_map(|(index, text)| {
+                    strings
+                        .get(index.wrapping_sub(1))
+                        .map(|extracted| (extracted.offset, text))
+                })
+                .collect()
+        };
+
+        let mut matches = self
+            .search_reader(std::io::Cursor::new(text.into_bytes()), pattern, path)
+            .await?;
+        for m in &mut matches {
+            if let Some(extracted) = strings.get(m.line_number.wrapping_sub(1)) {
+                let mut binary = BinaryMatch::string(extracted);
+                binary.strings_before = with_offsets(std::mem::take(&mut m.context_before));
+                binary.strings_after = with_offsets(std::mem::take(&mut m.context_after));
+                m.binary = Some(binary);
+            }
+            m.line_number = 0;
+        }
+
+        Ok(matches)
     }
 
     /// Search multiple files in parallel
@@ -123,37 +345,19 @@ impl StreamingSearchPipeline {
         pattern: &str,
         max_concurrent: usize,
     ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
-        let (tx, mut rx) = mpsc::channel::<RfgrepResult<Vec<ProcessorSearchMatch>>>(files.len());
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrent));
-        let config = Arc::new(self.config.clone());
-        let pattern = Arc::new(pattern.to_string());
-
-        // Spawn tasks for each file
-        for file_path in files {
-            let tx = tx.clone();
-            let semaphore = semaphore.clone();
-            let config = config.clone();
-            let pattern = pattern.clone();
-            let file_path = (*file_path).to_path_buf();
-
-            task::spawn(async move {
-                let _permit = semaphore.acquire().await.unwrap();
-                let pipeline = StreamingSearchPipeline::new((*config).clone());
-                let result = pipeline.search_file(&file_path, &pattern).await;
-                let _ = tx.send(result).await;
-            });
-        }
-
-        drop(tx); // Close the sender
+        let files = files.iter().map(|path| path.to_path_buf()).collect();
+        let mut rx = self.search_files_streaming(files, pattern, max_concurrent);
 
         // Collect results
         let mut all_matches = Vec::new();
         while let Some(result) = rx.recv().await {
             match result {
                 Ok(matches) => all_matches.extend(matches),
-                Err(e) => {
+                // Already recorded by the worker when a summary is attached
+                Err(e) if self.summary.is_none() => {
                     eprintln!("Error in parallel search: {e}");
                 }
+                Err(_) => {}
             }
         }
 
@@ -162,6 +366,67 @@ impl StreamingSearchPipeline {
         Ok(all_matches)
     }
 
+    /// Search files concurrently, sending each file's matches through a bounded
+    /// channel as soon as that file finishes.
+    ///
+    /// Every message holds the matches of exactly one file, in completion order;
+    /// files without matches send nothing. A worker keeps its concurrency permit
+    /// until the channel accepts its batch, so a slow reader bounds how many
+    /// finished results are held in memory. Must be called within a Tokio runtime.
+    pub fn search_files_streaming(
+        &self,
+        files: Vec<PathBuf>,
+        pattern: &str,
+        max_concurrent: usize,
+    ) -> mpsc::Receiver<RfgrepResult<Vec<ProcessorSearchMatch>>> {
+        let (tx, paths) = mpsc::channel(files.len().max(1));
+        for file_path in files {
+            // The channel has room for every file
+            let _ = tx.try_send(file_path);
+        }
+        self.search_paths_streaming(paths, pattern, max_concurrent)
+    }
+
+    /// Like [`search_files_streaming`](Self::search_files_streaming), but files are
+    /// taken from `paths` as they arrive, so searching can start while a directory
+    /// walk is still producing them. Dropping the returned receiver closes `paths`.
+    pub fn search_paths_streaming(
+        &self,
+        mut paths: mpsc::Receiver<PathBuf>,
+        pattern: &str,
+        max_concurrent: usize,
+    ) -> mpsc::Receiver<RfgrepResult<Vec<ProcessorSearchMatch>>> {
+        let max_concurrent = max_concurrent.max(1);
+        let (tx, rx) = mpsc::channel(max_concurrent * 2);
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrent));
+        let pipeline = self.clone();
+        let pattern = Arc::new(pattern.to_string());
+
+        task::spawn(async move {
+            while let Some(file_path) = paths.recv().await {
+                let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                    break;
+                };
+                if tx.is_closed() || pipeline.cancelled() {
+                    break;
+                }
+
+                let tx = tx.clone();
+                let pipeline = pipeline.clone();
+                let pattern = pattern.clone();
+                task::spawn(async move {
+                    let _permit = permit;
+                    let result = pipeline.search_file(&file_path, &pattern).await;
+                    if !matches!(&result, Ok(matches) if matches.is_empty()) {
+                        let _ = tx.send(result).await;
+                    }
+                });
+            }
+        });
+
+        rx
+    }
+
     fn create_search_algorithm(
         &self,
         pattern: &str,
@@ -191,19 +456,27 @@ impl StreamingSearchPipeline {
         ))
     }
 
+    /// Scan `reader` line by line, returning the matches and, if the run deadline or
+    /// cancellation interrupted the scan, the last line that was searched
     async fn process_file_streaming<R: Read + Send + 'static>(
         &self,
         reader: BufReader<R>,
         search_algo: &dyn SearchAlgorithmTrait,
         pattern: &str,
         _path: &Path,
-    ) -> RfgrepResult<Vec<SearchMatch>> {
+    ) -> RfgrepResult<(Vec<SearchMatch>, Option<usize>)> {
         let mut matches = Vec::new();
         let mut lines = reader.lines();
         let mut line_number = 0;
         let mut context_buffer = Vec::new();
 
         while let Some(line_result) = lines.next() {
+            if line_number % DEADLINE_CHECK_INTERVAL == 0
+                && (self.deadline_reached() || self.cancelled())
+            {
+                return Ok((matches, Some(line_number)));
+            }
+
             line_number += 1;
             let line = match line_result {
                 Ok(line) => line,
@@ -230,25 +503,23 @@ impl StreamingSearchPipeline {
                 let context_after =
                     self.get_context_after(&context_buffer, line_number, &mut lines)?;
 
-                let matched_text = if match_pos + 1 < line.len() {
-                    line[match_pos..].chars().take(50).collect::<String>()
-                } else {
-                    line.clone()
-                };
+                let match_end =
+                    (match_pos + search_algo.match_len(&line, match_pos, pattern)).min(line.len());
+                let matched_text = line.get(match_pos..match_end).unwrap_or_default();
 
                 matches.push(SearchMatch {
                     line_number,
                     line: line.clone(),
                     context_before,
                     context_after,
-                    matched_text,
+                    matched_text: matched_text.to_string(),
                     column_start: match_pos,
-                    column_end: match_pos + 1,
+                    column_end: match_end,
                 });
             }
         }
 
-        Ok(matches)
+        Ok((matches, None))
     }
 
     fn get_context_before(
@@ -311,6 +582,8 @@ impl StreamingSearchPipeline {
                     matched_text: search_match.matched_text,
                     column_start: search_match.column_start,
                     column_end: search_match.column_end,
+                    binary: None,
+                    revision: None,
                 });
             }
         }
@@ -408,4 +681,84 @@ mod tests {
         // let matches = pipeline.process_file_streaming(reader, &search_algo, Path::new("test.txt")).await.unwrap();
         // assert!(!matches.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_search_files_streaming_batches_per_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut files = Vec::new();
+        for i in 0..5 {
+            let path = temp_dir.path().join(format!("file{i}.txt"));
+            std::fs::write(&path, "test\nnothing\ntest again\n").unwrap();
+            files.push(path);
+        }
+        let empty = temp_dir.path().join("empty.txt");
+        std::fs::write(&empty, "nothing here\n").unwrap();
+        files.push(empty);
+
+        let config = StreamingConfig {
+            context_lines: 0,
+            ..StreamingConfig::default()
+        };
+        let pipeline = StreamingSearchPipeline::new(config);
+        let mut rx = pipeline.search_files_streaming(files, "test", 2);
+
+        let mut batches = Vec::new();
+        while let Some(result) = rx.recv().await {
+            batches.push(result.unwrap());
+        }
+
+        assert_eq!(batches.len(), 5);
+        for batch in &batches {
+            assert_eq!(batch.len(), 2);
+            assert!(batch.iter().all(|m| m.path == batch[0].path));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_string_matches_report_byte_offsets() {
+        let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+            context_lines: 1,
+            binary_strings: Some(4),
+            ..StreamingConfig::default()
+        });
+        let data = b"\x00\x01hello world\x00\x00\x02secret token\x00\x03more stuff\x00";
+        let matches = pipeline
+            .search_strings_in(data, "token", Path::new("f.bin"))
+            .await
+            .unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_number, 0);
+        assert!(matches[0].context_before.is_empty() && matches[0].context_after.is_empty());
+        let binary = matches[0].binary.as_ref().unwrap();
+        assert_eq!(binary.offset, 16);
+        assert_eq!(binary.strings_before, vec![(2, "hello world".to_string())]);
+        assert_eq!(binary.strings_after, vec![(30, "more stuff".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_matches_span_the_matched_text() {
+        let content = "let café = neeedle;\n";
+        for (algorithm, pattern) in [
+            (SearchAlgorithm::BoyerMoore, "neeedle"),
+            (SearchAlgorithm::Regex, "ne+dle"),
+        ] {
+            let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+                algorithm,
+                case_sensitive: true,
+                context_lines: 0,
+                ..StreamingConfig::default()
+            });
+            let matches = pipeline
+                .search_reader(Cursor::new(content), pattern, Path::new("x.rs"))
+                .await
+                .unwrap();
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].matched_text, "neeedle");
+            assert_eq!(
+                &matches[0].line[matches[0].column_start..matches[0].column_end],
+                "neeedle"
+            );
+        }
+    }
 }
@@ -0,0 +1,523 @@
+//! End-of-run search summary and grep-compatible exit status.
+//!
+//! Workers record every file they could not search (unreadable, timed out or
+//! skipped at search time) into a shared [`SearchSummary`]. At the end of the run
+//! the summary is reported on stderr, embedded in JSON/NDJSON output, and turned
+//! into the process exit status: `0` when something matched, `1` when nothing
+//! matched, `2` when any file failed or the run was cut short by `--deadline` or a
+//! resource budget, and `130` when it was interrupted with Ctrl-C.
+//!
+//! Files left out by classification or the size, binary and safety filters are
+//! counted by [`SkipReason`] for the report's breakdown; with `--show-skipped` each
+//! of them is also listed with its reason.
+use crate::budget::BudgetLimit;
+use crate::error::RfgrepError;
+use crate::processor::SearchMatch;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Process exit status, following grep's conventions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// At least one match (or a non-search command that completed)
+    Success,
+    /// The search completed without matches
+    NoMatch,
+    /// An error occurred
+    Error,
+    /// The run was interrupted (SIGINT)
+    Interrupted,
+}
+
+impl ExitStatus {
+    /// Numeric exit code for this status
+    pub fn code(self) -> u8 {
+        match self {
+            ExitStatus::Success => 0,
+            ExitStatus::NoMatch => 1,
+            ExitStatus::Error => 2,
+            ExitStatus::Interrupted => 130,
+        }
+    }
+}
+
+impl From<ExitStatus> for std::process::ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        std::process::ExitCode::from(status.code())
+    }
+}
+
+/// Why a file did not contribute to the results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// The file could not be opened or read
+    Error,
+    /// The search exceeded `--timeout-per-file`
+    Timeout,
+    /// The file was deliberately not searched
+    Skipped,
+    /// The run deadline passed before the file was searched
+    NotReached,
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueKind::Error => write!(f, "error"),
+            IssueKind::Timeout => write!(f, "timeout"),
+            IssueKind::Skipped => write!(f, "skipped"),
+            IssueKind::NotReached => write!(f, "not reached"),
+        }
+    }
+}
+
+/// Why a file was left out of the search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The extension is in the never-search set
+    NeverSearch,
+    /// The extension is in the skip-by-default set
+    SkipByDefault,
+    /// Neither the extension nor the contents identify a searchable type
+    UnknownType,
+    /// Larger than the type's size limit or `--max-size`
+    SizeLimit,
+    /// The contents are binary
+    Binary,
+    /// Not a regular file (device, socket, FIFO, kernel file system)
+    SpecialFile,
+    /// Rejected by `--safety-policy`
+    SafetyPolicy,
+    /// Rejected by `--include-extensions` or `--exclude-extensions`
+    ExtensionFilter,
+    /// Rejected by `--file-types` or `--text-only`
+    FileTypeStrategy,
+    /// The file's metadata could not be read
+    Unreadable,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SkipReason::NeverSearch => "never-search type",
+            SkipReason::SkipByDefault => "skipped type",
+            SkipReason::UnknownType => "unknown type",
+            SkipReason::SizeLimit => "size limit",
+            SkipReason::Binary => "binary",
+            SkipReason::SpecialFile => "special file",
+            SkipReason::SafetyPolicy => "safety policy",
+            SkipReason::ExtensionFilter => "extension filter",
+            SkipReason::FileTypeStrategy => "file type strategy",
+            SkipReason::Unreadable => "unreadable",
+        })
+    }
+}
+
+/// A file that could not be searched, with the reason
+#[derive(Debug, Clone, Serialize)]
+pub struct FileIssue {
+    pub path: PathBuf,
+    pub kind: IssueKind,
+    pub reason: String,
+    /// Category of a skipped file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<SkipReason>,
+}
+
+/// Thread-safe collector for per-file problems during a search
+#[derive(Debug, Default)]
+pub struct SearchSummary {
+    issues: Mutex<Vec<FileIssue>>,
+    files_searched: AtomicUsize,
+    files_with_matches: AtomicUsize,
+    matches: AtomicUsize,
+    partial: AtomicBool,
+    interrupted: AtomicBool,
+    /// The resource budget limit that stopped the run
+    budget_limit: OnceLock<BudgetLimit>,
+    /// Files left out of the search, by reason
+    skips: Mutex<BTreeMap<SkipReason, usize>>,
+    /// List every skipped file as an issue (`--show-skipped`)
+    show_skipped: bool,
+}
+
+impl SearchSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List every file left out by [`filtered`](Self::filtered) among the issues,
+    /// instead of only counting it
+    pub fn with_show_skipped(mut self, show_skipped: bool) -> Self {
+        self.show_skipped = show_skipped;
+        self
+    }
+
+    /// Record a problem with `path`
+    pub fn record(&self, path: &Path, kind: IssueKind, reason: impl Into<String>) {
+        self.push(FileIssue {
+            path: path.to_path_buf(),
+            kind,
+            reason: reason.into(),
+            skip_reason: None,
+        });
+    }
+
+    fn push(&self, issue: FileIssue) {
+        if let Ok(mut issues) = self.issues.lock() {
+            issues.push(issue);
+        }
+    }
+
+    fn count_skip(&self, reason: SkipReason) {
+        if let Ok(mut skips) = self.skips.lock() {
+            *skips.entry(reason).or_default() += 1;
+        }
+    }
+
+    /// Record a file that failed with `error`
+    pub fn error(&self, path: &Path, error: &RfgrepError) {
+        self.record(path, IssueKind::Error, error.to_string());
+    }
+
+    /// Record a file whose search was abandoned after `secs` seconds
+    pub fn timeout(&self, path: &Path, secs: u64) {
+        self.record(path, IssueKind::Timeout, format!("timed out after {secs}s"));
+    }
+
+    /// Record a file that was skipped at search time
+    pub fn skipped(&self, path: &Path, skip_reason: SkipReason, reason: impl Into<String>) {
+        self.count_skip(skip_reason);
+        self.push(FileIssue {
+            path: path.to_path_buf(),
+            kind: IssueKind::Skipped,
+            reason: reason.into(),
+            skip_reason: Some(skip_reason),
+        });
+    }
+
+    /// Count a file that file selection left out; it is only listed among the issues
+    /// with `--show-skipped`
+    pub fn filtered(&self, path: &Path, skip_reason: SkipReason, reason: impl Into<String>) {
+        if self.show_skipped {
+            self.skipped(path, skip_reason, reason);
+        } else {
+            self.count_skip(skip_reason);
+        }
+    }
+
+    /// Skipped files by reason
+    pub fn skip_counts(&self) -> BTreeMap<SkipReason, usize> {
+        self.skips
+            .lock()
+            .map(|skips| skips.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record a file that the run deadline cut off before it was searched
+    pub fn not_reached(&self, path: &Path, reason: impl Into<String>) {
+        self.mark_partial();
+        self.record(path, IssueKind::NotReached, reason);
+    }
+
+    /// Record a file whose search was interrupted by the run deadline, after
+    /// `line_number` lines when known
+    pub fn stopped_at_deadline(&self, path: &Path, line_number: Option<usize>) {
+        self.mark_partial();
+        let reason = match line_number {
+            Some(line_number) => format!("stopped at deadline after line {line_number}"),
+            None => "stopped at deadline".to_string(),
+        };
+        self.record(path, IssueKind::Timeout, reason);
+    }
+
+    /// Record a file left unsearched because `limit` of the resource budget was reached
+    pub fn budget_reached(&self, path: &Path, limit: BudgetLimit) {
+        self.mark_budget_exceeded(limit);
+        self.record(
+            path,
+            IssueKind::NotReached,
+            format!("{limit} reached before search started"),
+        );
+    }
+
+    /// Mark the results as partial because `limit` of the resource budget was reached
+    pub fn mark_budget_exceeded(&self, limit: BudgetLimit) {
+        self.mark_partial();
+        let _ = self.budget_limit.set(limit);
+    }
+
+    /// The resource budget limit that stopped the run, if any
+    pub fn budget_exceeded(&self) -> Option<BudgetLimit> {
+        self.budget_limit.get().copied()
+    }
+
+    /// Mark the results as partial because the run deadline was reached
+    pub fn mark_partial(&self) {
+        self.partial.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.partial.load(Ordering::Relaxed)
+    }
+
+    /// Mark the results as partial because the run was interrupted
+    pub fn mark_interrupted(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::Relaxed)
+    }
+
+    /// Count a file that was searched to completion
+    pub fn file_searched(&self) {
+        self.files_searched.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn files_searched(&self) -> usize {
+        self.files_searched.load(Ordering::Relaxed)
+    }
+
+    /// Count matches that were reported to the user
+    pub fn record_matches(&self, matches: &[SearchMatch]) {
+        let files = matches.iter().map(|m| &m.path).collect::<HashSet<_>>();
+        self.files_with_matches
+            .fetch_add(files.len(), Ordering::Relaxed);
+        self.matches.fetch_add(matches.len(), Ordering::Relaxed);
+    }
+
+    pub fn matches(&self) -> usize {
+        self.matches.load(Ordering::Relaxed)
+    }
+
+    /// Recorded issues, ordered by path
+    pub fn issues(&self) -> Vec<FileIssue> {
+        let mut issues = self
+            .issues
+            .lock()
+            .map(|issues| issues.clone())
+            .unwrap_or_default();
+        issues.sort_by(|a, b| a.path.cmp(&b.path));
+        issues
+    }
+
+    fn count(&self, kind: IssueKind) -> usize {
+        self.issues
+            .lock()
+            .map(|issues| issues.iter().filter(|i| i.kind == kind).count())
+            .unwrap_or_default()
+    }
+
+    /// Whether any file failed or timed out, or the run was cut short
+    pub fn has_errors(&self) -> bool {
+        self.is_partial() || self.count(IssueKind::Error) > 0 || self.count(IssueKind::Timeout) > 0
+    }
+
+    /// Exit status for the run
+    pub fn exit_status(&self) -> ExitStatus {
+        if self.is_interrupted() {
+            ExitStatus::Interrupted
+        } else if self.has_errors() {
+            ExitStatus::Error
+        } else if self.matches() > 0 {
+            ExitStatus::Success
+        } else {
+            ExitStatus::NoMatch
+        }
+    }
+
+    /// JSON `summary` object for structured output formats
+    pub fn to_json(&self) -> Value {
+        json!({
+            "files_searched": self.files_searched(),
+            "files_with_matches": self.files_with_matches.load(Ordering::Relaxed),
+            "matches": self.matches(),
+            "errors": self.count(IssueKind::Error),
+            "timeouts": self.count(IssueKind::Timeout),
+            "skipped": self.count(IssueKind::Skipped),
+            "not_reached": self.count(IssueKind::NotReached),
+            "skipped_by_reason": self.skip_counts(),
+            "partial": self.is_partial() || self.is_interrupted(),
+            "interrupted": self.is_interrupted(),
+            "budget_exceeded": self.budget_exceeded().map(|limit| limit.name()),
+            "issues": self.issues(),
+        })
+    }
+
+    /// Human-readable report of the recorded issues and skipped files, empty when
+    /// there are none
+    pub fn report(&self) -> String {
+        let issues = self.issues();
+        let skips = self.skip_counts();
+        let mut out = String::new();
+        if self.is_interrupted() {
+            out.push_str("Search interrupted; results are partial\n");
+        } else if let Some(limit) = self.budget_exceeded() {
+            out.push_str(&format!(
+                "Search stopped: {limit} reached; results are partial\n"
+            ));
+        } else if self.is_partial() {
+            out.push_str("Search stopped at the deadline; results are partial\n");
+        }
+        if !skips.is_empty() {
+            let total: usize = skips.values().sum();
+            let breakdown: Vec<String> = skips
+                .iter()
+                .map(|(reason, count)| format!("{count} {reason}"))
+                .collect();
+            out.push_str(&format!(
+                "{total} file{} skipped: {}\n",
+                if total == 1 { "" } else { "s" },
+                breakdown.join(", ")
+            ));
+        }
+        if issues.is_empty() {
+            return out;
+        }
+        out.push_str(&format!(
+            "{} file{} not searched:\n",
+            issues.len(),
+            if issues.len() == 1 { " was" } else { "s were" }
+        ));
+        for issue in issues {
+            out.push_str(&format!(
+                "  {}: {} ({})\n",
+                issue.path.display(),
+                issue.reason,
+                issue.kind
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search_match(path: &str, line_number: usize) -> SearchMatch {
+        SearchMatch {
+            path: PathBuf::from(path),
+            line_number,
+            line: "needle".to_string(),
+            context_before: vec![],
+            context_after: vec![],
+            matched_text: "needle".to_string(),
+            column_start: 0,
+            column_end: 6,
+            binary: None,
+            revision: None,
+        }
+    }
+
+    #[test]
+    fn test_exit_status_from_summary() {
+        let summary = SearchSummary::new();
+        assert_eq!(summary.exit_status(), ExitStatus::NoMatch);
+
+        summary.skipped(Path::new("a.bin"), SkipReason::Binary, "binary file");
+        assert_eq!(summary.exit_status(), ExitStatus::NoMatch);
+
+        summary.record_matches(&[search_match("a.txt", 1), search_match("a.txt", 3)]);
+        summary.record_matches(&[search_match("c.txt", 2)]);
+        assert_eq!(summary.exit_status(), ExitStatus::Success);
+        assert_eq!(summary.to_json()["matches"], 3);
+        assert_eq!(summary.to_json()["files_with_matches"], 2);
+
+        summary.timeout(Path::new("b.txt"), 1);
+        assert_eq!(summary.exit_status(), ExitStatus::Error);
+        assert_eq!(ExitStatus::Error.code(), 2);
+    }
+
+    #[test]
+    fn test_summary_json_and_report() {
+        let summary = SearchSummary::new();
+        summary.file_searched();
+        summary.error(
+            Path::new("z.txt"),
+            &RfgrepError::Other("permission denied".to_string()),
+        );
+        summary.skipped(Path::new("a.bin"), SkipReason::Binary, "binary file");
+
+        let value = summary.to_json();
+        assert_eq!(value["files_searched"], 1);
+        assert_eq!(value["errors"], 1);
+        assert_eq!(value["skipped"], 1);
+        assert_eq!(value["issues"][0]["path"], "a.bin");
+        assert_eq!(value["issues"][1]["kind"], "error");
+
+        assert_eq!(value["issues"][0]["skip_reason"], "binary");
+
+        let report = summary.report();
+        assert!(report.starts_with("1 file skipped: 1 binary\n2 files were not searched:"));
+        assert!(report.contains("  a.bin: binary file (skipped)"));
+    }
+
+    #[test]
+    fn test_filtered_files_are_counted_and_listed_on_request() {
+        let summary = SearchSummary::new();
+        summary.filtered(Path::new("big.log"), SkipReason::SizeLimit, "too large");
+        summary.filtered(Path::new("a.key"), SkipReason::NeverSearch, "never search");
+        summary.filtered(Path::new("b.key"), SkipReason::NeverSearch, "never search");
+        assert!(summary.issues().is_empty());
+        assert_eq!(summary.to_json()["skipped_by_reason"]["never_search"], 2);
+        assert_eq!(
+            summary.report(),
+            "3 files skipped: 2 never-search type, 1 size limit\n"
+        );
+
+        let summary = SearchSummary::new().with_show_skipped(true);
+        summary.filtered(Path::new("big.log"), SkipReason::SizeLimit, "too large");
+        assert_eq!(summary.issues()[0].skip_reason, Some(SkipReason::SizeLimit));
+        assert!(summary.report().contains("  big.log: too large (skipped)"));
+    }
+
+    #[test]
+    fn test_deadline_marks_summary_partial() {
+        let summary = SearchSummary::new();
+        summary.file_searched();
+        summary.record_matches(&[search_match("big.log", 7)]);
+        assert_eq!(summary.exit_status(), ExitStatus::Success);
+
+        summary.stopped_at_deadline(Path::new("big.log"), Some(1024));
+        summary.not_reached(Path::new("later.log"), "deadline reached");
+        assert!(summary.is_partial());
+        assert_eq!(summary.exit_status(), ExitStatus::Error);
+
+        let value = summary.to_json();
+        assert_eq!(value["partial"], true);
+        assert_eq!(value["timeouts"], 1);
+        assert_eq!(value["not_reached"], 1);
+        assert_eq!(value["issues"][1]["kind"], "not_reached");
+        assert!(summary
+            .report()
+            .starts_with("Search stopped at the deadline; results are partial\n"));
+
+        summary.mark_interrupted();
+        assert_eq!(summary.exit_status(), ExitStatus::Interrupted);
+        assert_eq!(ExitStatus::Interrupted.code(), 130);
+        assert_eq!(summary.to_json()["interrupted"], true);
+    }
+
+    #[test]
+    fn test_budget_limit_is_reported() {
+        let summary = SearchSummary::new();
+        summary.budget_reached(Path::new("late.log"), BudgetLimit::Files(2));
+        assert_eq!(summary.exit_status(), ExitStatus::Error);
+        assert_eq!(summary.to_json()["budget_exceeded"], "max_files");
+        assert!(summary
+            .report()
+            .starts_with("Search stopped: limit of 2 files reached; results are partial\n"));
+        assert!(summary
+            .report()
+            .contains("  late.log: limit of 2 files reached before search started"));
+    }
+}
@@ -1,9 +1,12 @@
 //! Modern TUI interface for rfgrep using ratatui
+use crate::cli::{self, Commands};
 use crate::error::Result as RfgrepResult;
 use crate::plugin_system::{EnhancedPluginManager, PluginRegistry};
 use crate::processor::SearchMatch;
+use crate::saved_searches::SavedSearches;
 use crate::search_algorithms::SearchAlgorithm;
 use crate::streaming_search::StreamingSearchPipeline;
+use crate::walker::WalkOptions;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent},
     execute,
@@ -90,6 +93,10 @@ pub struct TuiApp {
     table_state: TableState,
     scrollbar_state: ScrollbarState,
     should_quit: bool,
+    walk_options: WalkOptions,
+    skip_binary: bool,
+    roots: Vec<std::path::PathBuf>,
+    saved: SavedSearches,
 }
 
 impl TuiApp {
@@ -113,6 +120,10 @@ impl TuiApp {
             table_state,
             scrollbar_state: ScrollbarState::default(),
             should_quit: false,
+            walk_options: WalkOptions::default(),
+            skip_binary: false,
+            roots: Vec::new(),
+            saved: SavedSearches::default(),
         })
     }
 
@@ -327,6 +338,7 @@ impl TuiApp {
             "",
             "Search Input Mode:",
             "  Type pattern - Enter search pattern",
+            "  @NAME       - Run a saved search",
             "  Enter       - Execute search",
             "  Esc         - Cancel search",
             "  ←/→         - Move cursor",
@@ -557,17 +569,23 @@ impl TuiApp {
         self.state.search_in_progress = true;
         self.state.status_message = "Searching...".to_string();
 
-        use crate::walker::walk_dir;
-        use std::path::Path;
+        use crate::walker::walk_roots;
 
         let pattern = self.state.pattern.clone();
         let mut all_matches: Vec<SearchMatch> = Vec::new();
-        let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+        let roots = if self.roots.is_empty() {
+            vec![std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))]
+        } else {
+            self.roots.clone()
+        };
 
-        let entries: Vec<_> = walk_dir(Path::new(&cwd), true, false).collect();
+        let entries: Vec<_> =
+            walk_roots(&roots, true, &self.walk_options.or_defaults(false, true))?
+                .filter_map(Result::ok)
+                .collect();
         for entry in entries {
             let path = entry.path();
-            if path.is_file() {
+            if path.is_file() && !(self.skip_binary && crate::processor::is_binary(path)) {
                 let res = self.plugin_manager.search_file(path, &pattern).await;
                 if let Ok(mut matches) = res {
                     all_matches.append(&mut matches);
@@ -583,6 +601,26 @@ impl TuiApp {
         Ok(())
     }
 
+    /// Path filters applied when the TUI re-runs a search
+    pub fn set_walk_options(&mut self, walk_options: WalkOptions) {
+        self.walk_options = walk_options;
+    }
+
+    /// Directories the TUI searches when it re-runs a search; the working directory if empty
+    pub fn set_roots(&mut self, roots: Vec<std::path::PathBuf>) {
+        self.roots = roots;
+    }
+
+    /// Leave binary files out when the TUI re-runs a search
+    pub fn set_skip_binary(&mut self, skip_binary: bool) {
+        self.skip_binary = skip_binary;
+    }
+
+    /// Saved searches the search prompt runs when given `@NAME`
+    pub fn set_saved_searches(&mut self, saved: SavedSearches) {
+        self.saved = saved;
+    }
+
     pub fn set_pattern(&mut self, pattern: String) {
         self.state.pattern = pattern;
     }
@@ -601,6 +639,37 @@ impl TuiApp {
         self.state.files.sort();
     }
 
+    /// Take the pattern, mode, case sensitivity and algorithm of the saved search
+    /// `name`, returning false if there is no such search
+    fn apply_saved_search(&mut self, name: &str) -> bool {
+        let Some(Ok(saved)) = self.saved.get(name).map(|search| search.parse()) else {
+            return false;
+        };
+        let Commands::Search {
+            pattern,
+            mode,
+            case_sensitive,
+            algorithm,
+            ..
+        } = saved.command
+        else {
+            return false;
+        };
+        self.state.pattern = pattern;
+        self.state.case_sensitive = case_sensitive;
+        self.state.search_mode = match mode {
+            cli::SearchMode::Text => SearchMode::Text,
+            cli::SearchMode::Word => SearchMode::Word,
+            cli::SearchMode::Regex => SearchMode::Regex,
+        };
+        self.state.algorithm = match algorithm {
+            cli::SearchAlgorithm::BoyerMoore => SearchAlgorithm::BoyerMoore,
+            cli::SearchAlgorithm::Regex => SearchAlgorithm::Regex,
+            cli::SearchAlgorithm::Simple => SearchAlgorithm::Simple,
+        };
+        true
+    }
+
     /// Enter search input mode
     fn enter_search_input_mode(&mut self) {
         self.state.input_mode = InputMode::Search;
@@ -624,10 +693,17 @@ impl TuiApp {
         match key.code {
             KeyCode::Enter => {
                 // Apply the search
-                self.state.pattern = self.state.input_buffer.clone();
+                let input = std::mem::take(&mut self.state.input_buffer);
                 self.state.input_mode = InputMode::Normal;
-                self.state.input_buffer.clear();
                 self.state.input_cursor = 0;
+                if let Some(name) = input.strip_prefix('@') {
+                    if !self.apply_saved_search(name) {
+                        self.state.status_message = format!("No saved search named '{name}'");
+                        return Ok(false);
+                    }
+                } else {
+                    self.state.pattern = input;
+                }
                 self.state.status_message = "Searching...".to_string();
                 self.refresh_search().await?;
                 Ok(false)
@@ -1,15 +1,575 @@
-use ignore::{DirEntry, WalkBuilder};
-use std::path::Path;
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::file_filters::FileFilters;
+use crate::file_types::TypeFilter;
+use crate::summary::{FileIssue, IssueKind};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use std::collections::{HashSet, VecDeque};
+use std::fs::Metadata;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+/// Name of the project-specific ignore file, read alongside `.ignore`
+pub const RFGREP_IGNORE_FILENAME: &str = ".rfgrepignore";
+
+/// Path filters applied while walking a directory tree
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Globs a path must match to be included; a leading `!` excludes instead
+    pub globs: Vec<String>,
+    /// Like `globs`, but matched case-insensitively
+    pub iglobs: Vec<String>,
+    /// Include hidden files and directories; `None` leaves it to the command (see
+    /// [`WalkOptions::or_defaults`]) and otherwise skips them
+    pub hidden: Option<bool>,
+    /// Respect ignore files; `None` leaves it to the command and otherwise respects them
+    pub ignore: Option<bool>,
+    /// Don't respect `.gitignore` and `.git/info/exclude`
+    pub no_ignore_vcs: bool,
+    /// Don't respect `.ignore` and `.rfgrepignore`
+    pub no_ignore_dot: bool,
+    /// Don't respect the global gitignore (`core.excludesFile`)
+    pub no_ignore_global: bool,
+    /// Gitignore-style patterns to exclude, from the `[ignore]` config section
+    pub exclude: Vec<String>,
+    /// Skip files smaller than this many bytes
+    pub min_file_size: Option<u64>,
+    /// Follow symbolic links; loops are detected by device and inode and reported
+    pub follow: bool,
+    /// With `follow`, don't follow links that resolve outside the search root
+    pub stay_in_root: bool,
+    /// Walk at most this many levels below the root (1 = its direct entries); overrides `recursive`
+    pub max_depth: Option<usize>,
+    /// Don't descend into directories on a different device from the root
+    pub one_file_system: bool,
+    /// Time, owner and permission predicates for files
+    pub filters: FileFilters,
+    /// Named file types a file must belong to (`--type`)
+    pub types: Vec<String>,
+    /// Named file types to leave out (`--type-not`)
+    pub types_not: Vec<String>,
+    /// Extra type definitions, `name:glob` (`--type-add`)
+    pub type_add: Vec<String>,
+}
+
+impl WalkOptions {
+    /// Fill in the hidden-file and ignore-file settings not given on the command line
+    /// with a command's own defaults
+    pub fn or_defaults(&self, hidden: bool, ignore: bool) -> Self {
+        Self {
+            hidden: Some(self.hidden.unwrap_or(hidden)),
+            ignore: Some(self.ignore.unwrap_or(ignore)),
+            ..self.clone()
+        }
+    }
+
+    /// Build the glob overrides for a walk rooted at `root`. Globs are matched
+    /// against paths relative to the root, as in a `.gitignore` file.
+    fn overrides(&self, root: &Path) -> RfgrepResult<Override> {
+        let mut builder = OverrideBuilder::new(root);
+        for glob in &self.globs {
+            builder.add(glob).map_err(invalid_glob)?;
+        }
+        builder.case_insensitive(true).map_err(invalid_glob)?;
+        for glob in &self.iglobs {
+            builder.add(glob).map_err(invalid_glob)?;
+        }
+        builder.build().map_err(invalid_glob)
+    }
+
+    /// Build the matcher for the configured exclusion patterns
+    fn exclusions(&self, root: &Path) -> RfgrepResult<Gitignore> {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in &self.exclude {
+            builder.add_line(None, pattern).map_err(invalid_glob)?;
+        }
+        builder.build().map_err(invalid_glob)
+    }
+}
+
+/// Per-entry checks run while walking, so excluded directories are never descended into
+struct EntryFilter {
+    options: WalkOptions,
+    exclusions: Gitignore,
+    types: Option<TypeFilter>,
+    /// Canonical search root when links must not escape it
+    root: Option<PathBuf>,
+    issues: Arc<Mutex<VecDeque<FileIssue>>>,
+}
+
+impl EntryFilter {
+    fn new(
+        path: &Path,
+        options: &WalkOptions,
+        issues: Arc<Mutex<VecDeque<FileIssue>>>,
+    ) -> RfgrepResult<Self> {
+        Ok(Self {
+            options: options.clone(),
+            exclusions: options.exclusions(path)?,
+            types: TypeFilter::new(&options.type_add, &options.types, &options.types_not)?,
+            root: if options.follow && options.stay_in_root {
+                Some(path.canonicalize().map_err(RfgrepError::Io)?)
+            } else {
+                None
+            },
+            issues,
+        })
+    }
+
+    fn keep(&self, entry: &DirEntry) -> bool {
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        self.keep_path(
+            entry.path(),
+            is_dir,
+            entry.depth(),
+            entry.path_is_symlink(),
+            || entry.metadata().ok(),
+        )
+    }
+
+    /// Whether `path`, `depth` levels below the root, passes the exclusion patterns, type
+    /// filters, root confinement, size limit and metadata filters
+    fn keep_path(
+        &self,
+        path: &Path,
+        is_dir: bool,
+        depth: usize,
+        is_symlink: bool,
+        metadata: impl FnOnce() -> Option<Metadata>,
+    ) -> bool {
+        if depth > 0 && self.exclusions.matched(path, is_dir).is_ignore() {
+            return false;
+        }
+        if let Some(types) = &self.types {
+            if !is_dir && !types.matches(path) {
+                return false;
+            }
+        }
+        if let Some(root) = &self.root {
+            if is_symlink {
+                if let Ok(target) = path.canonicalize() {
+                    if !target.starts_with(root) {
+                        self.report(FileIssue {
+                            path: path.to_path_buf(),
+                            kind: IssueKind::Skipped,
+                            reason: format!(
+                                "symlink points outside the search root ({})",
+                                target.display()
+                            ),
+                            skip_reason: None,
+                        });
+                        return false;
+                    }
+                }
+            }
+        }
+        if is_dir || (self.options.min_file_size.is_none() && self.options.filters.is_empty()) {
+            return true;
+        }
+        let Some(metadata) = metadata() else {
+            return true;
+        };
+        self.options
+            .min_file_size
+            .is_none_or(|min| metadata.len() >= min)
+            && self.options.filters.matches(&metadata)
+    }
+
+    fn report(&self, issue: FileIssue) {
+        if let Ok(mut issues) = self.issues.lock() {
+            issues.push_back(issue);
+        }
+    }
+}
+
+/// Iterator over a walk started by [`walk_dir_with`]. Paths that could not be
+/// walked (broken links, symlink loops, unreadable directories) are yielded as
+/// [`FileIssue`]s alongside the entries.
+pub struct Walk {
+    inner: ignore::Walk,
+    issues: Arc<Mutex<VecDeque<FileIssue>>>,
+}
+
+impl Walk {
+    fn pending(&self) -> Option<FileIssue> {
+        self.issues.lock().ok()?.pop_front()
+    }
+}
+
+impl Iterator for Walk {
+    type Item = Result<DirEntry, FileIssue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(issue) = self.pending() {
+            return Some(Err(issue));
+        }
+        match self.inner.next() {
+            Some(Ok(entry)) => Some(Ok(entry)),
+            Some(Err(err)) => Some(Err(walk_issue(err))),
+            None => self.pending().map(Err),
+        }
+    }
+}
+
+/// A walk started by [`walk_dir_parallel`], run on several threads by [`ParallelWalk::run`]
+pub struct ParallelWalk {
+    inner: ignore::WalkParallel,
+    issues: Arc<Mutex<VecDeque<FileIssue>>>,
+}
+
+impl ParallelWalk {
+    /// Pass every entry and issue to `visit` from whichever thread found it, until
+    /// the walk is done or `visit` returns `false`
+    pub fn run<F>(self, visit: F)
+    where
+        F: Fn(Result<DirEntry, FileIssue>) -> bool + Sync,
+    {
+        let issues = &self.issues;
+        let pending = || issues.lock().ok()?.pop_front();
+        let visit = &visit;
+        self.inner.run(|| {
+            Box::new(move |entry| {
+                while let Some(issue) = pending() {
+                    if !visit(Err(issue)) {
+                        return WalkState::Quit;
+                    }
+                }
+                if visit(entry.map_err(walk_issue)) {
+                    WalkState::Continue
+                } else {
+                    WalkState::Quit
+                }
+            })
+        });
+        while let Some(issue) = pending() {
+            if !visit(Err(issue)) {
+                break;
+            }
+        }
+    }
+}
+
+/// Files already produced under an earlier search root. Overlapping roots such as
+/// `. src/` would otherwise yield the same file twice; the first root to reach a file
+/// keeps it, and the file is reported with that root's path.
+#[derive(Debug, Default)]
+pub struct SeenFiles {
+    seen: Option<Mutex<HashSet<PathBuf>>>,
+}
+
+impl SeenFiles {
+    /// Tracker for `roots`; files are only remembered when one root contains another
+    pub fn new(roots: &[PathBuf]) -> Self {
+        let canonical: Vec<PathBuf> = roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .collect();
+        let overlapping = canonical.iter().enumerate().any(|(i, root)| {
+            canonical
+                .iter()
+                .enumerate()
+                .any(|(j, other)| i != j && root.starts_with(other))
+        });
+        Self {
+            seen: overlapping.then(Default::default),
+        }
+    }
+
+    /// Whether `path` has not been produced before
+    pub fn first_visit(&self, path: &Path) -> bool {
+        let Some(seen) = &self.seen else {
+            return true;
+        };
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        seen.lock().map_or(true, |mut seen| seen.insert(key))
+    }
+}
+
+/// Describe a walk error as an issue for the run summary
+fn walk_issue(err: ignore::Error) -> FileIssue {
+    match err {
+        ignore::Error::WithDepth { err, .. } => walk_issue(*err),
+        ignore::Error::Loop { ancestor, child } => FileIssue {
+            path: child,
+            kind: IssueKind::Skipped,
+            reason: format!("symlink loop back to {}", ancestor.display()),
+            skip_reason: None,
+        },
+        ignore::Error::WithPath { path, err } => {
+            let is_link = path
+                .symlink_metadata()
+                .is_ok_and(|m| m.file_type().is_symlink());
+            let reason = if is_link && !path.exists() {
+                "broken symlink".to_string()
+            } else {
+                err.to_string()
+            };
+            FileIssue {
+                path,
+                kind: IssueKind::Error,
+                reason,
+                skip_reason: None,
+            }
+        }
+        err => FileIssue {
+            path: PathBuf::new(),
+            kind: IssueKind::Error,
+            reason: err.to_string(),
+            skip_reason: None,
+        },
+    }
+}
+
+fn invalid_glob(err: ignore::Error) -> RfgrepError {
+    RfgrepError::Other(format!("Invalid glob: {err}"))
+}
+
+/// Walk `path`; `show_hidden` includes hidden files and disables all ignore files
 pub fn walk_dir(path: &Path, recursive: bool, show_hidden: bool) -> impl Iterator<Item = DirEntry> {
-    let max_depth = if recursive { None } else { Some(1) };
-    WalkBuilder::new(path)
-        .hidden(!show_hidden)
-        .git_global(!show_hidden)
-        .git_ignore(!show_hidden)
-        .git_exclude(!show_hidden)
-        .ignore(!show_hidden)
-        .max_depth(max_depth)
+    let options = WalkOptions {
+        hidden: Some(show_hidden),
+        ignore: Some(!show_hidden),
+        ..WalkOptions::default()
+    };
+    walk_builder(path, recursive, &options)
         .build()
         .filter_map(Result::ok)
 }
+
+/// Walk `path`, applying the hidden-file, ignore-file, glob, exclusion and symlink settings in `options`
+pub fn walk_dir_with(path: &Path, recursive: bool, options: &WalkOptions) -> RfgrepResult<Walk> {
+    let (builder, issues) = filtered_walk_builder(path, recursive, options)?;
+    Ok(Walk {
+        inner: builder.build(),
+        issues,
+    })
+}
+
+/// Walk each of `roots` in turn with the same `options`, yielding every file once even
+/// when the roots overlap (see [`SeenFiles`])
+pub fn walk_roots(
+    roots: &[PathBuf],
+    recursive: bool,
+    options: &WalkOptions,
+) -> RfgrepResult<impl Iterator<Item = Result<DirEntry, FileIssue>>> {
+    let walks = roots
+        .iter()
+        .map(|root| walk_dir_with(root, recursive, options))
+        .collect::<RfgrepResult<Vec<_>>>()?;
+    let seen = SeenFiles::new(roots);
+    Ok(walks
+        .into_iter()
+        .flatten()
+        .filter(move |entry| match entry {
+            Ok(entry) if !entry.path().is_dir() => seen.first_visit(entry.path()),
+            _ => true,
+        }))
+}
+
+/// Like [`walk_dir_with`], but the walk is spread over `threads` threads
+pub fn walk_dir_parallel(
+    path: &Path,
+    recursive: bool,
+    options: &WalkOptions,
+    threads: usize,
+) -> RfgrepResult<ParallelWalk> {
+    let (mut builder, issues) = filtered_walk_builder(path, recursive, options)?;
+    let inner = builder.threads(threads).build_parallel();
+    Ok(ParallelWalk { inner, issues })
+}
+
+/// The checks a walk of `root` makes, for paths listed by another source such as git:
+/// globs, exclusion patterns, hidden files, `max_depth`, types, root confinement, size
+/// limit and metadata filters. Ignore files are left to the source.
+pub struct PathFilter {
+    root: PathBuf,
+    overrides: Override,
+    entries: EntryFilter,
+    hidden: bool,
+    max_depth: Option<usize>,
+    issues: Arc<Mutex<VecDeque<FileIssue>>>,
+}
+
+impl PathFilter {
+    pub fn new(root: &Path, options: &WalkOptions) -> RfgrepResult<Self> {
+        let issues = Arc::new(Mutex::new(VecDeque::new()));
+        Ok(Self {
+            root: root.to_path_buf(),
+            overrides: options.overrides(root)?,
+            entries: EntryFilter::new(root, options, issues.clone())?,
+            hidden: options.hidden.unwrap_or(false),
+            max_depth: options.max_depth,
+            issues,
+        })
+    }
+
+    /// Whether the file `path` on disk, under the root, passes every check
+    pub fn keep_file(&self, path: &Path) -> bool {
+        let is_symlink = path
+            .symlink_metadata()
+            .is_ok_and(|m| m.file_type().is_symlink());
+        self.keep(path, is_symlink, || path.metadata().ok())
+    }
+
+    /// Whether a stored file of `size` bytes at `path`, relative to the root, passes the
+    /// path checks and size limit. It has no metadata on disk, so the time, owner and
+    /// permission filters don't apply.
+    pub fn keep_stored(&self, path: &Path, size: u64) -> bool {
+        self.entries
+            .options
+            .min_file_size
+            .is_none_or(|min| size >= min)
+            && self.keep(&self.root.join(path), false, || None)
+    }
+
+    fn keep(
+        &self,
+        path: &Path,
+        is_symlink: bool,
+        metadata: impl FnOnce() -> Option<Metadata>,
+    ) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let depth = relative.components().count();
+        if self.max_depth.is_some_and(|max| depth > max) {
+            return false;
+        }
+        if !self.hidden
+            && relative
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+        {
+            return false;
+        }
+        let dirs_kept = relative
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .all(|dir| {
+                let depth = dir.components().count();
+                let dir = self.root.join(dir);
+                !self.overrides.matched(&dir, true).is_ignore()
+                    && self.entries.keep_path(&dir, true, depth, false, || None)
+            });
+        dirs_kept
+            && !self.overrides.matched(path, false).is_ignore()
+            && self
+                .entries
+                .keep_path(path, false, depth, is_symlink, metadata)
+    }
+
+    /// Paths the checks reported on, such as links leaving the root
+    pub fn issues(&self) -> Vec<FileIssue> {
+        self.issues
+            .lock()
+            .map(|mut issues| issues.drain(..).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Apply the checks of a walk of `root` to `files` listed by another source (see
+/// [`PathFilter`]). Files are kept in order.
+pub fn filter_listed(
+    root: &Path,
+    options: &WalkOptions,
+    files: impl IntoIterator<Item = PathBuf>,
+) -> RfgrepResult<Vec<Result<PathBuf, FileIssue>>> {
+    let filter = PathFilter::new(root, options)?;
+    let mut kept: Vec<_> = files
+        .into_iter()
+        .filter(|path| filter.keep_file(path))
+        .map(Ok)
+        .collect();
+    kept.extend(filter.issues().into_iter().map(Err));
+    Ok(kept)
+}
+
+/// Walk builder with the glob overrides and the per-entry filter for `options` installed
+fn filtered_walk_builder(
+    path: &Path,
+    recursive: bool,
+    options: &WalkOptions,
+) -> RfgrepResult<(WalkBuilder, Arc<Mutex<VecDeque<FileIssue>>>)> {
+    let overrides = options.overrides(path)?;
+    let issues = Arc::new(Mutex::new(VecDeque::new()));
+    let filter = EntryFilter::new(path, options, issues.clone())?;
+    let mut builder = walk_builder(path, recursive, options);
+    builder
+        .overrides(overrides)
+        .filter_entry(move |entry| filter.keep(entry));
+    Ok((builder, issues))
+}
+
+fn walk_builder(path: &Path, recursive: bool, options: &WalkOptions) -> WalkBuilder {
+    let max_depth = options.max_depth.or(if recursive { None } else { Some(1) });
+    let ignore = options.ignore.unwrap_or(true);
+    let vcs = ignore && !options.no_ignore_vcs;
+    let dot = ignore && !options.no_ignore_dot;
+
+    let mut builder = WalkBuilder::new(path);
+    builder
+        .hidden(!options.hidden.unwrap_or(false))
+        .git_ignore(vcs)
+        .git_exclude(vcs)
+        .git_global(vcs && !options.no_ignore_global)
+        .ignore(dot)
+        .follow_links(options.follow)
+        .same_file_system(options.one_file_system)
+        .max_depth(max_depth);
+    if dot {
+        builder.add_custom_ignore_filename(RFGREP_IGNORE_FILENAME);
+    }
+    builder
+}
+
+/// Returns true if `path` is the conventional `-` placeholder for standard input
+pub fn is_stdin_path(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Read an explicit list of paths from a file, or from standard input when `source` is `-`.
+///
+/// Entries are separated by newlines, or by NUL bytes when `null_separated` is set
+/// (as produced by `find -print0` or `git ls-files -z`). Empty entries are ignored.
+pub fn read_path_list(source: &Path, null_separated: bool) -> RfgrepResult<Vec<PathBuf>> {
+    let data = if is_stdin_path(source) {
+        let mut buf = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut buf)
+            .map_err(RfgrepError::Io)?;
+        buf
+    } else {
+        std::fs::read(source).map_err(RfgrepError::Io)?
+    };
+
+    Ok(split_path_list(&data, null_separated))
+}
+
+pub(crate) fn split_path_list(data: &[u8], null_separated: bool) -> Vec<PathBuf> {
+    let separator = if null_separated { b'\0' } else { b'\n' };
+    data.split(|&b| b == separator)
+        .map(|entry| {
+            if null_separated {
+                entry
+            } else {
+                entry.strip_suffix(b"\r").unwrap_or(entry)
+            }
+        })
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
@@ -122,7 +122,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
 
     // 9: Global Option --log with search
     let log_search = "search_test.log";
@@ -135,7 +135,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
     assert!(fs::metadata(log_search).is_ok());
     let _ = fs::remove_file(log_search);
 
@@ -160,7 +160,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--path-flag")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
 
     // 12: Global Option --path with list
     Command::new(&bin)
@@ -179,7 +179,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
 
     // 14: Search Command --mode word
     Command::new(&bin)
@@ -190,7 +190,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
 
     // 15: Search Command --dry-run
     Command::new(&bin)
@@ -0,0 +1,64 @@
+//! Helpers shared by the integration tests; each test binary uses only some of them
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A temporary directory holding `files`, given as relative path and contents; parent
+/// directories are created as needed
+pub fn corpus<P, C>(
+    files: impl IntoIterator<Item = (P, C)>,
+) -> Result<TempDir, Box<dyn std::error::Error>>
+where
+    P: AsRef<Path>,
+    C: AsRef<[u8]>,
+{
+    let temp_dir = TempDir::new()?;
+    for (path, contents) in files {
+        let path = temp_dir.path().join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+    }
+    Ok(temp_dir)
+}
+
+/// Files under `root` that `rfgrep search needle --recursive -l ARGS -- ROOT` reports
+pub fn matched_files(args: &[&str], root: &Path) -> Vec<String> {
+    listed_files(&["search", "needle", "--recursive", "-l"], args, root)
+}
+
+/// Files under `root` printed one per line by `rfgrep --no-config COMMAND ARGS -- ROOT`
+pub fn listed_files(command: &[&str], args: &[&str], root: &Path) -> Vec<String> {
+    let output = Command::cargo_bin("rfgrep")
+        .unwrap()
+        .arg("--no-config")
+        .args(command)
+        .args(args)
+        .arg("--")
+        .arg(root)
+        .output()
+        .unwrap();
+    relative_paths(&output.stdout, root)
+}
+
+/// Paths printed one per line, made relative to `root` (or to `.` when they start with
+/// `./`), `/`-separated and sorted
+pub fn relative_paths(stdout: &[u8], root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = String::from_utf8_lossy(stdout)
+        .lines()
+        .map(|line| {
+            let path = Path::new(line);
+            path.strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .trim_start_matches("./")
+                .replace('\\', "/")
+        })
+        .collect();
+    files.sort();
+    files
+}
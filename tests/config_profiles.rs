@@ -0,0 +1,175 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const PROJECT_CONFIG: &str = r#"
+[search]
+recursive = true
+
+[profiles.ci.search]
+output_format = "json"
+"#;
+
+const SUBDIR_CONFIG: &str = r#"
+[search]
+default_extensions = ["rs"]
+"#;
+
+/// A project whose root config searches recursively and whose `app/` config
+/// narrows the search to Rust files
+fn project() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("app/src"))?;
+    fs::write(root.join(".rfgrep.toml"), PROJECT_CONFIG)?;
+    fs::write(root.join("app/.rfgrep.toml"), SUBDIR_CONFIG)?;
+    fs::write(root.join("app/src/main.rs"), "// needle\n")?;
+    fs::write(root.join("app/src/notes.txt"), "needle\n")?;
+    Ok(temp_dir)
+}
+
+fn rfgrep(root: &Path, dir: &str) -> Command {
+    let mut command = Command::cargo_bin("rfgrep").unwrap();
+    command
+        .current_dir(root.join(dir))
+        .env("HOME", root)
+        .env("XDG_CONFIG_HOME", root.join(".config"));
+    command
+}
+
+fn matched_files(command: &mut Command) -> Vec<String> {
+    let output = command.output().unwrap();
+    common::relative_paths(&output.stdout, Path::new("."))
+}
+
+#[test]
+fn config_files_cascade_and_flags_win() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+    assert_eq!(
+        matched_files(rfgrep(root, "app").args(["search", "needle", "-l", "--", "."])),
+        vec!["src/main.rs"]
+    );
+    assert_eq!(
+        matched_files(rfgrep(root, "app").args([
+            "search",
+            "needle",
+            "-l",
+            "--include-extensions",
+            "txt",
+            "--",
+            "."
+        ])),
+        vec!["src/notes.txt"]
+    );
+    // Outside app/, only the root config applies
+    assert_eq!(
+        matched_files(rfgrep(root, ".").args(["search", "needle", "-l", "--", "."])),
+        vec!["app/src/main.rs", "app/src/notes.txt"]
+    );
+    assert!(matched_files(rfgrep(root, "app").args([
+        "--no-config",
+        "search",
+        "needle",
+        "-l",
+        "--",
+        "."
+    ]))
+    .is_empty());
+    Ok(())
+}
+
+#[test]
+fn profile_overlays_the_merged_config() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+    let output = rfgrep(root, "app")
+        .args(["search", "needle", "--profile", "ci", "--", "."])
+        .output()?;
+    assert!(output.status.success());
+    let document: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(document["total_matches"], 1);
+
+    rfgrep(root, "app")
+        .args(["search", "needle", "--profile", "nightly", "--", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Unknown profile 'nightly' (available: ci)",
+        ));
+    Ok(())
+}
+
+#[test]
+fn config_show_prints_sources_and_merged_settings() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+    let output = rfgrep(root, "app")
+        .args(["config", "show", "--profile", "ci"])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let root_config = stdout.find(".rfgrep.toml").unwrap();
+    let app_config = stdout.find("app/.rfgrep.toml").unwrap();
+    assert!(root_config < app_config);
+    assert!(stdout.contains("# Profile: ci\n"));
+    assert!(stdout.contains("recursive = true\n"));
+    assert!(stdout.contains("output_format = \"json\"\n"));
+    Ok(())
+}
+
+#[test]
+fn config_validate_reports_invalid_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+    rfgrep(root, "app")
+        .args(["config", "validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("app/.rfgrep.toml: ok"));
+
+    fs::write(root.join("app/.rfgrep.toml"), "[search]\ncontext = 2\n")?;
+    rfgrep(root, "app")
+        .args(["config", "validate"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("unknown field `context`"))
+        .stderr(predicate::str::contains("1 config problem found"));
+    rfgrep(root, "app")
+        .args(["search", "needle", "--", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("app/.rfgrep.toml"));
+    Ok(())
+}
+
+#[test]
+fn config_init_writes_a_starter_file() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    rfgrep(root, ".")
+        .args(["config", "init"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(root.join(".rfgrep.toml"))?.contains("[search]"));
+    rfgrep(root, ".")
+        .args(["config", "init"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("use --force to overwrite it"));
+    rfgrep(root, ".")
+        .args(["config", "init", "--global"])
+        .assert()
+        .success();
+    assert!(root.join(".config/rfgrep/config.toml").is_file());
+    rfgrep(root, ".")
+        .args(["config", "validate"])
+        .assert()
+        .success();
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn deadline_reports_partial_results() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(temp_dir.path().join(name), "needle\n")?;
+    }
+
+    let output = Command::cargo_bin("rfgrep")?
+        .env("RFGREP_WORKER_SLEEP", "3")
+        .arg("search")
+        .arg("needle")
+        .arg("--recursive")
+        .arg("--deadline")
+        .arg("500ms")
+        .arg("--output-format")
+        .arg("json")
+        .arg("--")
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .get_output()
+        .stdout
+        .clone();
+
+    let value: serde_json::Value = serde_json::from_slice(&output)?;
+    let summary = &value["summary"];
+    assert_eq!(summary["partial"], true);
+    assert_eq!(summary["timeouts"], 1);
+    assert_eq!(summary["not_reached"], 2);
+    let interrupted = summary["issues"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|issue| issue["reason"] == "stopped at deadline")
+        .count();
+    assert_eq!(interrupted, 1);
+
+    Ok(())
+}
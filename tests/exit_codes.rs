@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn exit_code_reflects_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let file = temp_dir.path().join("file.txt");
+    fs::write(&file, "needle\n")?;
+
+    Command::cargo_bin("rfgrep")?
+        .arg("search")
+        .arg("needle")
+        .arg("--")
+        .arg(&file)
+        .assert()
+        .code(0);
+
+    Command::cargo_bin("rfgrep")?
+        .arg("search")
+        .arg("absent")
+        .arg("--")
+        .arg(&file)
+        .assert()
+        .code(1);
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_lists_files_and_succeeds() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let file = temp_dir.path().join("file.txt");
+    fs::write(&file, "haystack\n")?;
+
+    Command::cargo_bin("rfgrep")?
+        .args(["--no-config", "search", "absent", "--dry-run", "--"])
+        .arg(&file)
+        .assert()
+        .code(0)
+        .stdout(format!("{}\n", file.display()));
+
+    Ok(())
+}
+
+#[test]
+fn ndjson_ends_with_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let file = temp_dir.path().join("file.txt");
+    let missing = temp_dir.path().join("missing.txt");
+    fs::write(&file, "needle\n")?;
+
+    let list = format!("{}\n{}\n", file.display(), missing.display());
+    let output = Command::cargo_bin("rfgrep")?
+        .arg("search")
+        .arg("needle")
+        .arg("--ndjson")
+        .arg("--files-from")
+        .arg("-")
+        .write_stdin(list)
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8(output)?;
+    let last = stdout.lines().last().unwrap_or_default();
+    let value: serde_json::Value = serde_json::from_str(last)?;
+    assert_eq!(value["summary"]["files_searched"], 1);
+    assert_eq!(value["summary"]["skipped"], 1);
+    assert_eq!(
+        value["summary"]["issues"][0]["reason"],
+        "not a regular file"
+    );
+    assert!(predicate::str::contains("missing.txt").eval(
+        value["summary"]["issues"][0]["path"]
+            .as_str()
+            .unwrap_or_default()
+    ));
+
+    Ok(())
+}
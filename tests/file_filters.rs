@@ -0,0 +1,88 @@
+mod common;
+
+use assert_cmd::Command;
+use common::matched_files;
+use std::fs::{self, File};
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+const DAY: Duration = Duration::from_secs(86400);
+
+/// `old.txt` was last modified three days ago, `new.txt` just now
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = common::corpus([("old.txt", "needle\n"), ("new.txt", "needle\n")])?;
+    File::options()
+        .write(true)
+        .open(temp_dir.path().join("old.txt"))?
+        .set_modified(SystemTime::now() - 3 * DAY)?;
+    Ok(temp_dir)
+}
+
+#[test]
+fn time_filters_select_by_modification_time() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(
+        matched_files(&["--changed-within", "1d"], root),
+        vec!["new.txt"]
+    );
+    assert_eq!(
+        matched_files(&["--changed-before", "2d"], root),
+        vec!["old.txt"]
+    );
+    assert_eq!(
+        matched_files(&["--changed-before", "2000-01-01"], root),
+        Vec::<String>::new()
+    );
+
+    let reference = root.join("old.txt");
+    let newer = reference.to_str().unwrap();
+    assert_eq!(matched_files(&["--newer", newer], root), vec!["new.txt"]);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn permission_filters_select_by_mode() -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+    fs::write(root.join("run.sh"), "needle\n")?;
+    fs::set_permissions(root.join("run.sh"), fs::Permissions::from_mode(0o755))?;
+    fs::set_permissions(root.join("new.txt"), fs::Permissions::from_mode(0o666))?;
+    fs::set_permissions(root.join("old.txt"), fs::Permissions::from_mode(0o644))?;
+
+    assert_eq!(matched_files(&["--executable"], root), vec!["run.sh"]);
+    assert_eq!(matched_files(&["--perm", "-o+w"], root), vec!["new.txt"]);
+    assert_eq!(matched_files(&["--perm", "644"], root), vec!["old.txt"]);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn owner_filter_resolves_users() -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::MetadataExt;
+
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+    let uid = fs::metadata(root.join("new.txt"))?.uid().to_string();
+
+    assert_eq!(
+        matched_files(&["--owner", &uid], root),
+        vec!["new.txt", "old.txt"]
+    );
+
+    Command::cargo_bin("rfgrep")?
+        .arg("search")
+        .arg("needle")
+        .arg("--owner")
+        .arg("no-such-user-rfgrep")
+        .arg("--")
+        .arg(root)
+        .assert()
+        .code(2)
+        .stderr(predicates::str::contains("Unknown user"));
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const CONFIG: &str = r#"
+[file_types]
+always = ["exe"]
+never = [".md"]
+
+[file_types.size_limits]
+log = 8
+"#;
+
+/// A project whose `.rfgrep.toml` searches `.exe` files, never searches Markdown and
+/// caps `.log` files at 8 bytes
+fn project() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::write(root.join(".rfgrep.toml"), CONFIG)?;
+    fs::write(root.join("tool.exe"), "needle in a script\n")?;
+    fs::write(root.join("notes.md"), "needle\n")?;
+    fs::write(root.join("small.log"), "needle\n")?;
+    fs::write(root.join("large.log"), "a long line with a needle\n")?;
+    fs::write(root.join("main.txt"), "needle\n")?;
+    Ok(temp_dir)
+}
+
+fn rfgrep(root: &Path) -> Command {
+    let mut command = Command::cargo_bin("rfgrep").unwrap();
+    command
+        .current_dir(root)
+        .env("HOME", root)
+        .env("XDG_CONFIG_HOME", root.join(".config"));
+    command
+}
+
+#[test]
+fn file_types_config_changes_what_is_searched() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let output = rfgrep(temp_dir.path())
+        .args(["search", "needle", "-l", "--", "."])
+        .output()?;
+    let mut files: Vec<String> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| line.trim_start_matches("./").to_string())
+        .collect();
+    files.sort();
+    assert_eq!(files, vec!["main.txt", "small.log", "tool.exe"]);
+    Ok(())
+}
+
+#[test]
+fn explain_reports_rule_and_size_limit() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    rfgrep(temp_dir.path())
+        .args(["explain", "tool.exe", "notes.md", "large.log", "main.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "tool.exe: search (full text)\n  rule: extension `.exe` is in always_search (config)",
+        ))
+        .stdout(predicate::str::contains(
+            "notes.md: skip: Never search file type: md\n  rule: extension `.md` is in never_search (config)",
+        ))
+        .stdout(predicate::str::contains("large.log: skip: File too large"))
+        .stdout(predicate::str::contains("size limit: 8 bytes for .log (config)"))
+        .stdout(predicate::str::contains(
+            "rule: extension `.txt` is in always_search (built-in)",
+        ));
+    Ok(())
+}
+
+#[test]
+fn explain_fails_for_missing_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    rfgrep(temp_dir.path())
+        .args(["explain", "missing.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Cannot read missing.txt"));
+    Ok(())
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use assert_cmd::Command;
+use common::matched_files;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    common::corpus([
+        ("src/main.rs", "needle\n"),
+        ("Cargo.toml", "needle = 1\n"),
+        ("Dockerfile", "RUN needle\n"),
+        ("Makefile", "needle:\n"),
+        ("api.proto", "// needle\n"),
+        ("app.js", "needle();\n"),
+    ])
+}
+
+#[test]
+fn type_selects_named_types() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(
+        matched_files(&["--type", "rust"], root),
+        vec!["src/main.rs"]
+    );
+    assert_eq!(
+        matched_files(&["-t", "docker", "-t", "make"], root),
+        vec!["Dockerfile", "Makefile"]
+    );
+    Ok(())
+}
+
+#[test]
+fn type_not_excludes_named_types() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    let files = matched_files(&["--type-not", "js", "-T", "rust"], root);
+    assert!(!files.contains(&"app.js".to_string()));
+    assert!(!files.contains(&"src/main.rs".to_string()));
+    assert!(files.contains(&"Cargo.toml".to_string()));
+    Ok(())
+}
+
+#[test]
+fn type_add_defines_new_types() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(
+        matched_files(&["--type-add", "proto:*.proto", "--type", "proto"], root),
+        vec!["api.proto"]
+    );
+    assert_eq!(
+        matched_files(&["--type-add", "rust:Cargo.toml", "--type", "rust"], root),
+        vec!["Cargo.toml", "src/main.rs"]
+    );
+    Ok(())
+}
+
+#[test]
+fn types_command_lists_definitions() {
+    Command::cargo_bin("rfgrep")
+        .unwrap()
+        .args(["types", "--type-add", "proto:*.proto"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rust: *.rs\n"))
+        .stdout(predicate::str::contains(
+            "docker: *.dockerfile, Containerfile, Dockerfile, Dockerfile.*\n",
+        ))
+        .stdout(predicate::str::contains("proto: *.proto\n"));
+}
+
+#[test]
+fn list_applies_type_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+
+    Command::cargo_bin("rfgrep")
+        .unwrap()
+        .args(["--type", "make", "list", "--recursive", "--"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Makefile"))
+        .stdout(predicate::str::contains("main.rs").not());
+    Ok(())
+}
+
+#[test]
+fn unknown_type_is_an_error() {
+    Command::cargo_bin("rfgrep")
+        .unwrap()
+        .args(["search", "needle", "--type", "nope", "--", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unrecognized file type: nope"));
+}
+
+#[test]
+fn type_detects_extensionless_scripts() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("bin"))?;
+    fs::write(
+        root.join("bin/deploy"),
+        "#!/usr/bin/env python3\nprint('needle')\n",
+    )?;
+    fs::write(root.join("bin/setup"), "#!/bin/sh -e\necho needle\n")?;
+    fs::write(root.join("bin/notes"), "needle\n# vim: set ft=ruby:\n")?;
+    fs::write(root.join("Jenkinsfile"), "stage('needle') {}\n")?;
+    fs::write(root.join("tool.py"), "needle = 1\n")?;
+
+    assert_eq!(
+        matched_files(&["--type", "python"], root),
+        vec!["bin/deploy", "tool.py"]
+    );
+    assert_eq!(matched_files(&["-t", "ruby"], root), vec!["bin/notes"]);
+    assert_eq!(matched_files(&["-t", "groovy"], root), vec!["Jenkinsfile"]);
+    assert_eq!(
+        matched_files(&["-T", "shell"], root),
+        vec!["Jenkinsfile", "bin/deploy", "bin/notes", "tool.py"]
+    );
+    Ok(())
+}
@@ -0,0 +1,99 @@
+#![cfg(unix)]
+
+mod common;
+
+use assert_cmd::Command;
+use std::os::unix::fs::symlink;
+use tempfile::TempDir;
+
+/// `root/` holds a file, a link back to its own parent, a broken link and a
+/// link to a sibling directory outside the root
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = common::corpus([("root/pkg/a.txt", "needle\n"), ("shared/b.txt", "needle\n")])?;
+    let root = temp_dir.path().join("root");
+    symlink("../pkg", root.join("pkg/loop"))?;
+    symlink("missing.txt", root.join("broken.txt"))?;
+    symlink("../shared", root.join("shared"))?;
+    Ok(temp_dir)
+}
+
+fn summary(
+    args: &[&str],
+    temp_dir: &TempDir,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("rfgrep")?
+        .current_dir(temp_dir.path())
+        .arg("search")
+        .arg("needle")
+        .arg("--recursive")
+        .arg("--output-format")
+        .arg("json")
+        .args(args)
+        .arg("--")
+        .arg("root")
+        .output()?;
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    Ok(value["summary"].clone())
+}
+
+fn issue<'a>(summary: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    summary["issues"]
+        .as_array()?
+        .iter()
+        .find(|issue| issue["path"] == path)
+}
+
+#[test]
+fn symlinked_directories_are_not_followed_by_default() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let summary = summary(&[], &temp_dir)?;
+    assert_eq!(summary["matches"], 1);
+    assert_eq!(summary["issues"], serde_json::json!([]));
+    Ok(())
+}
+
+#[test]
+fn follow_reports_loops_and_broken_links() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let summary = summary(&["--follow"], &temp_dir)?;
+    assert_eq!(summary["matches"], 2);
+    assert_eq!(
+        issue(&summary, "root/broken.txt").unwrap()["reason"],
+        "broken symlink"
+    );
+    assert_eq!(issue(&summary, "root/pkg/loop").unwrap()["kind"], "skipped");
+    Ok(())
+}
+
+#[test]
+fn stay_in_root_does_not_escape() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let summary = summary(&["--follow", "--stay-in-root"], &temp_dir)?;
+    assert_eq!(summary["matches"], 1);
+    let escaped = issue(&summary, "root/shared").unwrap();
+    assert_eq!(escaped["kind"], "skipped");
+    assert!(escaped["reason"]
+        .as_str()
+        .unwrap()
+        .starts_with("symlink points outside the search root"));
+    Ok(())
+}
+
+#[test]
+fn processor_skips_symlinked_files_only_without_follow() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let link = temp_dir.path().join("root/a.txt");
+    symlink("pkg/a.txt", &link)?;
+    let pattern = regex::Regex::new("needle")?;
+
+    assert_eq!(
+        rfgrep::processor::search_file_with(&link, &pattern, true)?.len(),
+        1
+    );
+    assert!(rfgrep::processor::search_file_with(&link, &pattern, false)?.is_empty());
+
+    let metadata = link.symlink_metadata()?;
+    assert!(rfgrep::processor::should_skip(&link, &metadata, false));
+    assert!(!rfgrep::processor::should_skip(&link, &metadata, true));
+    Ok(())
+}
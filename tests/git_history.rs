@@ -0,0 +1,211 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::process;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) {
+    let status = process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "user.name=rfgrep",
+            "-c",
+            "user.email=rfgrep@example.com",
+        ])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A repository where `needle` is added in the second commit (tagged `v2`) and
+/// removed again in the third, which is checked out
+fn repository() -> Option<TempDir> {
+    process::Command::new("git")
+        .arg("--version")
+        .output()
+        .ok()?;
+
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    git(root, &["init", "-q"]);
+    fs::write(root.join("notes.txt"), "haystack\n").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "first"]);
+    fs::write(root.join("notes.txt"), "haystack\nneedle here\n").unwrap();
+    git(root, &["commit", "-q", "-a", "-m", "second"]);
+    git(root, &["tag", "v2"]);
+    fs::write(root.join("notes.txt"), "haystack\n").unwrap();
+    git(root, &["commit", "-q", "-a", "-m", "third"]);
+    Some(temp_dir)
+}
+
+fn search(args: &[&str], root: &Path) -> Vec<Value> {
+    let output = Command::cargo_bin("rfgrep")
+        .unwrap()
+        .arg("search")
+        .arg("needle")
+        .arg("--ndjson")
+        .args(args)
+        .arg("--")
+        .arg(root)
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .filter(|value| value.get("revision").is_some())
+        .collect()
+}
+
+fn rev_parse(root: &Path, rev: &str) -> String {
+    let output = process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["rev-parse", rev])
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn rev_searches_an_older_tree_without_checking_it_out() {
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+    let root = temp_dir.path();
+
+    assert!(search(&[], root).is_empty());
+
+    let matches = search(&["--rev", "v2"], root);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["line"], "needle here");
+    assert_eq!(matches[0]["revision"]["commit"], rev_parse(root, "v2"));
+    assert_eq!(matches[0]["revision"]["path"], "notes.txt");
+    assert!(matches[0]["revision"].get("change").is_none());
+
+    assert_eq!(
+        fs::read_to_string(root.join("notes.txt")).unwrap(),
+        "haystack\n"
+    );
+}
+
+#[test]
+fn history_reports_the_commits_that_added_and_removed_a_match() {
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+    let root = temp_dir.path();
+
+    let matches = search(&["--history"], root);
+    let changes: Vec<(String, String)> = matches
+        .iter()
+        .map(|m| {
+            (
+                m["revision"]["change"].as_str().unwrap().to_string(),
+                m["revision"]["commit"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("removed".to_string(), rev_parse(root, "HEAD")),
+            ("added".to_string(), rev_parse(root, "HEAD~1")),
+        ]
+    );
+
+    // Starting from an older revision only sees the history up to it
+    let matches = search(&["--history", "--rev", "v2"], root);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["revision"]["change"], "added");
+}
+
+#[test]
+fn unknown_revision_is_an_error() {
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+
+    Command::cargo_bin("rfgrep")
+        .unwrap()
+        .args(["search", "needle", "--rev", "no-such-tag", "--"])
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicates::str::contains("Unknown revision 'no-such-tag'"));
+}
+
+/// Paths of the matches, sorted
+fn matched_paths(matches: &[Value]) -> Vec<String> {
+    let mut paths: Vec<String> = matches
+        .iter()
+        .map(|m| m["revision"]["path"].as_str().unwrap().to_string())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn revision_blobs_go_through_the_file_filters_and_budget() {
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+    let root = temp_dir.path();
+    fs::write(root.join("lib.rs"), "needle\n").unwrap();
+    fs::write(root.join("todo.txt"), "needle\n").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "fourth"]);
+
+    assert_eq!(
+        matched_paths(&search(&["--rev", "HEAD"], root)),
+        vec!["lib.rs", "todo.txt"]
+    );
+    assert_eq!(
+        matched_paths(&search(&["--rev", "HEAD", "--glob", "*.rs"], root)),
+        vec!["lib.rs"]
+    );
+    assert_eq!(
+        matched_paths(&search(
+            &["--rev", "HEAD", "--exclude-extensions", "rs"],
+            root
+        )),
+        vec!["todo.txt"]
+    );
+    assert_eq!(
+        search(&["--rev", "HEAD", "--max-files", "1"], root).len(),
+        1
+    );
+    assert_eq!(
+        matched_paths(&search(&["--history", "--glob", "*.rs"], root)),
+        vec!["lib.rs"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn revision_paths_that_are_not_utf8_are_searched() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+    let root = temp_dir.path();
+    fs::write(root.join(OsStr::from_bytes(b"caf\xe9.txt")), "needle\n").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "fourth"]);
+
+    assert_eq!(
+        matched_paths(&search(&["--rev", "HEAD"], root)),
+        vec!["caf\u{fffd}.txt"]
+    );
+    assert_eq!(
+        matched_paths(&search(&["--history"], root)),
+        vec!["caf\u{fffd}.txt", "notes.txt", "notes.txt"]
+    );
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use assert_cmd::Command;
+use common::matched_files;
+use std::fs;
+use std::path::Path;
+use std::process;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) {
+    let status = process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "user.name=rfgrep",
+            "-c",
+            "user.email=rfgrep@example.com",
+        ])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A repository with one committed file of each state: unchanged, modified,
+/// staged, and an untracked and an ignored file
+fn repository() -> Option<TempDir> {
+    process::Command::new("git")
+        .arg("--version")
+        .output()
+        .ok()?;
+
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    git(root, &["init", "-q"]);
+    for name in ["unchanged.txt", "modified.txt", "staged.txt"] {
+        fs::write(root.join(name), "needle\n").unwrap();
+    }
+    fs::write(root.join(".gitignore"), "ignored.txt\n").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "initial"]);
+
+    fs::write(root.join("modified.txt"), "needle\nmore\n").unwrap();
+    fs::write(root.join("staged.txt"), "needle\nstaged\n").unwrap();
+    git(root, &["add", "staged.txt"]);
+    fs::write(root.join("untracked.txt"), "needle\n").unwrap();
+    fs::write(root.join("ignored.txt"), "needle\n").unwrap();
+    Some(temp_dir)
+}
+
+#[test]
+fn git_options_select_files_from_the_repository() {
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+    let root = temp_dir.path();
+
+    assert_eq!(
+        matched_files(&["--git-changed"], root),
+        vec!["modified.txt", "staged.txt"]
+    );
+    assert_eq!(matched_files(&["--git-staged"], root), vec!["staged.txt"]);
+    assert_eq!(
+        matched_files(&["--git-untracked"], root),
+        vec!["untracked.txt"]
+    );
+    assert_eq!(
+        matched_files(&["--git-tracked-only"], root),
+        vec!["modified.txt", "staged.txt", "unchanged.txt"]
+    );
+    assert_eq!(
+        matched_files(&["--git-staged", "--git-untracked"], root),
+        vec!["staged.txt", "untracked.txt"]
+    );
+}
+
+#[test]
+fn git_options_outside_a_repository_fail() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("a.txt"), "needle\n")?;
+
+    Command::cargo_bin("rfgrep")?
+        .env("GIT_CEILING_DIRECTORIES", temp_dir.path().parent().unwrap())
+        .arg("search")
+        .arg("needle")
+        .arg("--git-tracked-only")
+        .arg("--")
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicates::str::contains("git ls-files"));
+    Ok(())
+}
+
+#[test]
+fn git_files_go_through_the_walk_filters() {
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/nested")).unwrap();
+    fs::write(root.join("src/lib.rs"), "needle\n").unwrap();
+    fs::write(root.join("src/nested/deep.rs"), "needle\n").unwrap();
+    git(root, &["add", "src"]);
+
+    assert_eq!(
+        matched_files(&["--git-changed", "--glob", "*.rs"], root),
+        vec!["src/lib.rs", "src/nested/deep.rs"]
+    );
+    assert_eq!(
+        matched_files(&["--git-changed", "--glob", "!src/nested/"], root),
+        vec!["modified.txt", "src/lib.rs", "staged.txt"]
+    );
+    assert_eq!(
+        matched_files(&["--git-staged", "--max-depth", "2"], root),
+        vec!["src/lib.rs", "staged.txt"]
+    );
+}
+
+#[test]
+fn git_tracked_only_narrows_the_other_git_options() {
+    let Some(temp_dir) = repository() else {
+        return;
+    };
+    let root = temp_dir.path();
+
+    assert_eq!(
+        matched_files(&["--git-changed=HEAD", "--git-tracked-only"], root),
+        vec!["modified.txt", "staged.txt"]
+    );
+    assert_eq!(
+        matched_files(&["--git-staged", "--git-tracked-only"], root),
+        vec!["staged.txt"]
+    );
+}
+
+#[test]
+fn git_changed_takes_its_ref_only_after_an_equals_sign() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("rfgrep")?
+        .args(["search", "needle", "--git-changed", "HEAD", "--", "."])
+        .assert()
+        .code(2)
+        .stderr(predicates::str::contains("unexpected argument 'HEAD'"));
+    Ok(())
+}
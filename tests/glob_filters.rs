@@ -0,0 +1,58 @@
+mod common;
+
+use assert_cmd::Command;
+use common::matched_files;
+use tempfile::TempDir;
+
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    common::corpus([
+        ("src/lib.rs", "needle\n"),
+        ("src/MAIN.RS", "needle\n"),
+        ("src/generated/out.rs", "needle\n"),
+        ("docs/notes.md", "needle\n"),
+    ])
+}
+
+#[test]
+fn glob_includes_and_excludes_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+
+    let files = matched_files(
+        &["--glob", "src/**/*.rs", "--glob", "!**/generated/**"],
+        temp_dir.path(),
+    );
+    assert_eq!(files, vec!["src/lib.rs"]);
+
+    Ok(())
+}
+
+#[test]
+fn iglob_matches_case_insensitively() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+
+    let files = matched_files(&["--iglob", "*.rs"], temp_dir.path());
+    assert_eq!(
+        files,
+        vec!["src/MAIN.RS", "src/generated/out.rs", "src/lib.rs"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn invalid_glob_is_an_error() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+
+    Command::cargo_bin("rfgrep")?
+        .arg("search")
+        .arg("needle")
+        .arg("--glob")
+        .arg("src/{a")
+        .arg("--")
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicates::str::contains("Invalid glob"));
+
+    Ok(())
+}
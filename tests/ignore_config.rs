@@ -0,0 +1,91 @@
+mod common;
+
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const CONFIG: &str = r#"
+[ignore]
+patterns = ["vendor"]
+hidden_files = false
+min_file_size = 8
+"#;
+
+/// A project whose `.rfgrep.toml` excludes `vendor/`, includes hidden files
+/// and skips files under 8 bytes
+fn project() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("vendor"))?;
+    fs::write(root.join(".rfgrep.toml"), CONFIG)?;
+    fs::write(root.join("main.txt"), "needle here\n")?;
+    fs::write(root.join("tiny.txt"), "needle\n")?;
+    fs::write(root.join(".env"), "needle here\n")?;
+    fs::write(root.join("vendor/lib.txt"), "needle here\n")?;
+    Ok(temp_dir)
+}
+
+fn matched_files(args: &[&str], root: &Path) -> Vec<String> {
+    let output = Command::cargo_bin("rfgrep")
+        .unwrap()
+        .current_dir(root)
+        .env("HOME", root)
+        .env("XDG_CONFIG_HOME", root.join(".config"))
+        .arg("search")
+        .arg("needle")
+        .arg("--recursive")
+        .arg("-l")
+        .args(args)
+        .arg("--")
+        .arg(".")
+        .output()
+        .unwrap();
+    common::relative_paths(&output.stdout, root)
+}
+
+#[test]
+fn ignore_config_applies_to_search() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    assert_eq!(
+        matched_files(&[], temp_dir.path()),
+        vec![".env", "main.txt"]
+    );
+    Ok(())
+}
+
+#[test]
+fn no_config_uses_builtin_defaults() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    assert_eq!(
+        matched_files(&["--no-config"], temp_dir.path()),
+        vec![".env", "main.txt", "tiny.txt", "vendor/lib.txt"]
+    );
+    Ok(())
+}
+
+#[test]
+fn ignore_config_applies_to_list() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+
+    let output = Command::cargo_bin("rfgrep")?
+        .current_dir(temp_dir.path())
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join(".config"))
+        .arg("list")
+        .arg("--recursive")
+        .arg("--")
+        .arg(".")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8(output)?;
+    assert!(stdout.contains("main.txt"));
+    assert!(stdout.contains(".env"));
+    assert!(!stdout.contains("tiny.txt"));
+    assert!(!stdout.contains("vendor"));
+    Ok(())
+}
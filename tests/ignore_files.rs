@@ -0,0 +1,108 @@
+mod common;
+
+use common::matched_files;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A fake repository with a gitignored build directory, a hidden file and
+/// paths excluded by `.ignore` and `.rfgrepignore`
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = common::corpus([
+        (".gitignore", "target/\n"),
+        (".ignore", "scratch.txt\n"),
+        (".rfgrepignore", "fixtures.txt\n"),
+        ("main.txt", "needle\n"),
+        (".env", "needle\n"),
+        ("target/build.txt", "needle\n"),
+        ("scratch.txt", "needle\n"),
+        ("fixtures.txt", "needle\n"),
+    ])?;
+    fs::create_dir_all(temp_dir.path().join(".git"))?;
+    Ok(temp_dir)
+}
+
+fn listed_files(args: &[&str], root: &Path) -> Vec<String> {
+    common::listed_files(&["list", "--recursive", "--simple"], args, root)
+}
+
+#[test]
+fn search_includes_hidden_and_ignored_files_by_default() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    assert_eq!(
+        matched_files(&[], temp_dir.path()),
+        vec![
+            ".env",
+            "fixtures.txt",
+            "main.txt",
+            "scratch.txt",
+            "target/build.txt"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn search_respects_ignore_files_with_ignore() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(matched_files(&["--ignore"], root), vec![".env", "main.txt"]);
+    assert_eq!(
+        matched_files(&["--ignore", "--no-hidden"], root),
+        vec!["main.txt"]
+    );
+    Ok(())
+}
+
+#[test]
+fn ignore_toggles_are_independent() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(
+        matched_files(&["--ignore", "--no-hidden", "--no-ignore-vcs"], root),
+        vec!["main.txt", "target/build.txt"]
+    );
+    assert_eq!(
+        matched_files(&["--ignore", "--no-hidden", "--no-ignore-dot"], root),
+        vec!["fixtures.txt", "main.txt", "scratch.txt"]
+    );
+    assert_eq!(
+        listed_files(&["--no-ignore"], root),
+        vec![
+            "fixtures.txt",
+            "main.txt",
+            "scratch.txt",
+            "target/build.txt"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn list_skips_hidden_and_ignored_files_unless_show_hidden() -> Result<(), Box<dyn std::error::Error>>
+{
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(listed_files(&[], root), vec!["main.txt"]);
+    assert_eq!(
+        listed_files(&["--hidden"], root),
+        vec![".env", ".gitignore", ".ignore", ".rfgrepignore", "main.txt"]
+    );
+    assert_eq!(
+        listed_files(&["--show-hidden"], root),
+        vec![
+            ".env",
+            ".gitignore",
+            ".ignore",
+            ".rfgrepignore",
+            "fixtures.txt",
+            "main.txt",
+            "scratch.txt",
+            "target/build.txt"
+        ]
+    );
+    Ok(())
+}
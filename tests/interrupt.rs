@@ -0,0 +1,47 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tempfile::TempDir;
+
+#[test]
+fn interrupt_flushes_partial_results() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(temp_dir.path().join(name), "needle\n")?;
+    }
+
+    let child = Command::new(assert_cmd::cargo::cargo_bin("rfgrep"))
+        .env("RFGREP_WORKER_SLEEP", "3")
+        .arg("--threads")
+        .arg("1")
+        .arg("search")
+        .arg("needle")
+        .arg("--recursive")
+        .arg("--timeout-per-file")
+        .arg("30")
+        .arg("--ndjson")
+        .arg("--")
+        .arg(temp_dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    std::thread::sleep(Duration::from_secs(1));
+    Command::new("kill")
+        .arg("-INT")
+        .arg(child.id().to_string())
+        .status()?;
+
+    let output = child.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(130));
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let last = stdout.lines().last().unwrap_or_default();
+    let value: serde_json::Value = serde_json::from_str(last)?;
+    assert_eq!(value["summary"]["interrupted"], true);
+    assert_eq!(value["summary"]["partial"], true);
+
+    Ok(())
+}
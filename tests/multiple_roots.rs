@@ -0,0 +1,111 @@
+mod common;
+
+use assert_cmd::Command;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    common::corpus([
+        ("src/lib.rs", "needle\n"),
+        ("src/nested/mod.rs", "needle\n"),
+        ("tests/it.rs", "needle\n"),
+        ("benches/bench.rs", "needle\n"),
+    ])
+}
+
+fn rfgrep(root: &Path, args: &[&str]) -> Vec<String> {
+    let output = Command::cargo_bin("rfgrep")
+        .unwrap()
+        .current_dir(root)
+        .args(args)
+        .output()
+        .unwrap();
+    let mut lines: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn search_walks_every_root() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(
+        rfgrep(
+            root,
+            &[
+                "search",
+                "needle",
+                "-l",
+                "--recursive",
+                "--",
+                "src",
+                "tests"
+            ]
+        ),
+        vec!["src/lib.rs", "src/nested/mod.rs", "tests/it.rs"]
+    );
+    assert_eq!(
+        rfgrep(
+            root,
+            &[
+                "search",
+                "needle",
+                "-l",
+                "--recursive",
+                "--path-flag",
+                "benches",
+                "--",
+                "tests"
+            ]
+        ),
+        vec!["benches/bench.rs", "tests/it.rs"]
+    );
+    Ok(())
+}
+
+#[test]
+fn overlapping_roots_report_each_file_once() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    // Collected and streamed searches deduplicate the same way
+    for extra in [&["--sort", "path"][..], &[][..]] {
+        let mut args = vec!["search", "needle", "-l", "--recursive"];
+        args.extend(extra);
+        args.extend(["--", "src", "src/nested", "src"]);
+        assert_eq!(rfgrep(root, &args), vec!["src/lib.rs", "src/nested/mod.rs"]);
+    }
+
+    let output = Command::cargo_bin("rfgrep")
+        .unwrap()
+        .current_dir(root)
+        .args([
+            "search",
+            "needle",
+            "--count",
+            "--recursive",
+            "--",
+            ".",
+            "tests",
+        ])
+        .output()?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), "4");
+    Ok(())
+}
+
+#[test]
+fn list_walks_every_root() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    let files = rfgrep(root, &["list", "--recursive", "--", "tests", "benches"]);
+    assert!(files.contains(&"tests/it.rs".to_string()));
+    assert!(files.contains(&"benches/bench.rs".to_string()));
+    assert!(!files.iter().any(|file| file.starts_with("src/")));
+    Ok(())
+}
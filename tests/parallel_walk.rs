@@ -0,0 +1,53 @@
+mod common;
+
+use common::matched_files;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn parallel_walk_finds_every_file_once() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    let mut expected = Vec::new();
+    for d in 0..12 {
+        let dir = root.join(format!("dir{d:02}/nested"));
+        fs::create_dir_all(&dir)?;
+        for f in 0..15 {
+            let name = format!("file{f:02}.txt");
+            if f % 3 == 0 {
+                fs::write(dir.join(&name), "haystack\n")?;
+            } else {
+                fs::write(dir.join(&name), "needle\n")?;
+                expected.push(format!("dir{d:02}/nested/{name}"));
+            }
+        }
+        fs::write(dir.join("skip.bin"), [0u8, 1, 2, b'n', 0])?;
+    }
+    expected.sort();
+
+    for threads in ["1", "4"] {
+        assert_eq!(matched_files(&["--threads", threads], root), expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn parallel_walk_applies_globs_and_extension_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/deep"))?;
+    fs::write(root.join("src/lib.rs"), "needle\n")?;
+    fs::write(root.join("src/deep/mod.rs"), "needle\n")?;
+    fs::write(root.join("src/deep/notes.md"), "needle\n")?;
+    fs::write(root.join("README.md"), "needle\n")?;
+
+    assert_eq!(
+        matched_files(&["--glob", "src/**"], root),
+        vec!["src/deep/mod.rs", "src/deep/notes.md", "src/lib.rs"]
+    );
+    assert_eq!(
+        matched_files(&["--include-extensions", "rs"], root),
+        vec!["src/deep/mod.rs", "src/lib.rs"]
+    );
+    Ok(())
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// Five matching files of 100 bytes each
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    common::corpus((0..5).map(|i| {
+        let line = format!("needle {i}\n");
+        (format!("file{i}.txt"), format!("{line:<99}\n"))
+    }))
+}
+
+fn rfgrep() -> Command {
+    let mut command = Command::cargo_bin("rfgrep").unwrap();
+    command.arg("--no-config");
+    command
+}
+
+fn json_summary(command: &mut Command) -> Result<Value, Box<dyn std::error::Error>> {
+    let output = command.output()?;
+    assert_eq!(output.status.code(), Some(2));
+    let document: Value = serde_json::from_slice(&output.stdout)?;
+    Ok(document["summary"].clone())
+}
+
+#[test]
+fn file_budget_stops_the_search_with_a_report() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let output = rfgrep()
+        .args([
+            "search",
+            "needle",
+            "--max-files",
+            "2",
+            "--threads",
+            "1",
+            "--",
+        ])
+        .arg(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Search stopped: limit of 2 files reached; results are partial\n",
+        ))
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(stdout.matches("needle ").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn byte_budget_is_reported_in_json() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let summary = json_summary(
+        rfgrep()
+            .args([
+                "search",
+                "needle",
+                "--max-bytes",
+                "250",
+                "--output-format",
+                "json",
+                "--",
+            ])
+            .arg(temp_dir.path()),
+    )?;
+    assert_eq!(summary["budget_exceeded"], "max_bytes");
+    assert_eq!(summary["partial"], true);
+    assert_eq!(summary["files_searched"], 2);
+    Ok(())
+}
+
+#[test]
+fn unlimited_runs_report_no_budget() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let output = rfgrep()
+        .args(["search", "needle", "--output-format", "json", "--"])
+        .arg(temp_dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let document: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(document["summary"]["budget_exceeded"], Value::Null);
+    assert_eq!(document["summary"]["files_searched"], 5);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn memory_budget_stops_before_searching() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let summary = json_summary(
+        rfgrep()
+            .args([
+                "search",
+                "needle",
+                "--max-memory",
+                "1",
+                "--output-format",
+                "json",
+                "--",
+            ])
+            .arg(temp_dir.path()),
+    )?;
+    assert_eq!(summary["budget_exceeded"], "max_memory");
+    assert_eq!(summary["files_searched"], 0);
+    Ok(())
+}
+
+#[test]
+fn invalid_sizes_are_rejected() {
+    rfgrep()
+        .args(["search", "needle", "--max-bytes", "5Q", "--", "."])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown size unit 'Q'"));
+}
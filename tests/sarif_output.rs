@@ -0,0 +1,74 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+use tempfile::TempDir;
+
+fn sarif(dir: &TempDir, args: &[&str]) -> Result<(Option<i32>, Value), Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("rfgrep")?
+        .current_dir(dir.path())
+        .args(["--no-config", "search", "--output-format", "sarif"])
+        .args(args)
+        .output()?;
+    Ok((
+        output.status.code(),
+        serde_json::from_slice(&output.stdout)?,
+    ))
+}
+
+#[test]
+fn matches_become_sarif_results() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::create_dir_all(temp_dir.path().join("src"))?;
+    fs::write(
+        temp_dir.path().join("src/lib.rs"),
+        "fn a() {}\n// TODO: tidy up\nfn b() {}\n",
+    )?;
+
+    let (code, log) = sarif(
+        &temp_dir,
+        &["TODO", "--recursive", "--context-lines", "1", "--", "."],
+    )?;
+    assert_eq!(code, Some(0));
+    assert_eq!(log["version"], "2.1.0");
+
+    let run = &log["runs"][0];
+    let driver = &run["tool"]["driver"];
+    assert_eq!(driver["name"], "rfgrep");
+    assert_eq!(driver["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(driver["rules"][0]["id"], "TODO");
+    let invocation = &run["invocations"][0];
+    assert_eq!(invocation["executionSuccessful"], true);
+    assert!(invocation["arguments"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("sarif")));
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "TODO");
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "src/lib.rs");
+    assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+    let region = &location["region"];
+    assert_eq!(region["startLine"], 2);
+    assert_eq!(region["startColumn"], 4);
+    assert_eq!(region["endColumn"], 8);
+    assert_eq!(location["contextRegion"]["startLine"], 1);
+    assert_eq!(location["contextRegion"]["endLine"], 3);
+    Ok(())
+}
+
+#[test]
+fn runs_without_matches_still_write_a_log() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("notes.txt"), "nothing here\n")?;
+
+    let (code, log) = sarif(&temp_dir, &["TODO", "--", "."])?;
+    assert_eq!(code, Some(1));
+    assert_eq!(log["runs"][0]["results"], Value::Array(vec![]));
+    assert_eq!(
+        log["runs"][0]["invocations"][0]["properties"]["summary"]["files_searched"],
+        1
+    );
+    Ok(())
+}
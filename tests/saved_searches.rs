@@ -0,0 +1,126 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A project with a TODO in `src/` and another outside it
+fn project() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("app/src"))?;
+    fs::write(root.join("app/src/main.rs"), "// TODO(ann): tidy\n")?;
+    fs::write(root.join("app/notes.txt"), "TODO later\n")?;
+    Ok(temp_dir)
+}
+
+fn rfgrep(root: &Path, dir: &str) -> Command {
+    let mut command = Command::cargo_bin("rfgrep").unwrap();
+    command
+        .current_dir(root.join(dir))
+        .env("HOME", root)
+        .env("XDG_CONFIG_HOME", root.join(".config"));
+    command
+}
+
+#[test]
+fn saved_search_runs_with_its_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+    rfgrep(root, "app")
+        .args(["saved", "add", "owners", "--", "TODO(", "-l", "--", "src"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".config/rfgrep/saved.toml"));
+    assert!(root.join(".config/rfgrep/saved.toml").is_file());
+
+    rfgrep(root, "app")
+        .args(["saved", "run", "owners"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("main.rs"))
+        .stdout(predicate::str::contains("notes.txt").not());
+
+    rfgrep(root, "app")
+        .args(["saved", "run", "missing"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "No saved search named 'missing' (saved: owners)",
+        ));
+    Ok(())
+}
+
+#[test]
+fn project_searches_are_shared_and_override_user_ones() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+    rfgrep(root, "app")
+        .args(["saved", "add", "todo", "--", "TODO", "-l", "--", "src"])
+        .assert()
+        .success();
+    rfgrep(root, "app")
+        .args([
+            "saved",
+            "add",
+            "todo",
+            "--project",
+            "--description",
+            "Every TODO",
+        ])
+        .args(["--", "TODO", "-l", "--recursive", "--", "."])
+        .assert()
+        .success();
+    assert!(root.join("app/.rfgrep-saved.toml").is_file());
+
+    // A second add of the same name needs --force
+    rfgrep(root, "app")
+        .args(["saved", "add", "todo", "--project", "--", "FIXME"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("use --force to replace it"));
+
+    rfgrep(root, "app/src")
+        .args(["saved", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "todo (project): rfgrep search TODO -l --recursive -- .\n    Every TODO\n",
+        ));
+    rfgrep(root, "app")
+        .args(["saved", "run", "todo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("notes.txt"));
+
+    // Removing the project search uncovers the user one
+    rfgrep(root, "app")
+        .args(["saved", "remove", "todo"])
+        .assert()
+        .success();
+    rfgrep(root, "app")
+        .args(["saved", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "todo (user): rfgrep search TODO -l -- src\n",
+        ));
+    Ok(())
+}
+
+#[test]
+fn invalid_search_arguments_are_not_saved() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+    rfgrep(root, "app")
+        .args(["saved", "add", "broken", "--", "TODO", "--no-such-flag"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid search arguments"));
+    rfgrep(root, "app")
+        .args(["saved", "list"])
+        .assert()
+        .success()
+        .stdout("No saved searches\n");
+    Ok(())
+}
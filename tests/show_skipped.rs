@@ -0,0 +1,117 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// One searchable file and one file for each of several skip reasons
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    common::corpus([
+        ("notes.txt", "needle\n"),
+        ("server.pem", "needle\n"),
+        ("app.exe", "needle\n"),
+        ("large.txt", "needle\n".repeat(200_000).as_str()),
+    ])
+}
+
+fn rfgrep() -> Command {
+    let mut command = Command::cargo_bin("rfgrep").unwrap();
+    command.arg("--no-config");
+    command
+}
+
+#[test]
+fn summary_counts_skipped_files_by_reason() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    rfgrep()
+        .args(["search", "needle", "--max-size", "1", "--"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "3 files skipped: 1 never-search type, 1 skipped type, 1 size limit\n",
+        ))
+        .stderr(predicate::str::contains("server.pem").not());
+    Ok(())
+}
+
+#[test]
+fn show_skipped_lists_each_file_with_its_reason() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    rfgrep()
+        .args([
+            "search",
+            "needle",
+            "--max-size",
+            "1",
+            "--show-skipped",
+            "--",
+        ])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "server.pem: Never search file type: pem (skipped)",
+        ))
+        .stderr(predicate::str::contains(
+            "app.exe: Skip by default: exe (skipped)",
+        ))
+        .stderr(predicate::str::contains(
+            "large.txt: 1400000 bytes > --max-size 1 MB (skipped)",
+        ));
+
+    let output = rfgrep()
+        .args([
+            "search",
+            "needle",
+            "--max-size",
+            "1",
+            "--show-skipped",
+            "--ndjson",
+            "--",
+        ])
+        .arg(temp_dir.path())
+        .output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let summary: Value = serde_json::from_str(stdout.lines().last().unwrap())?;
+    let summary = &summary["summary"];
+    assert_eq!(summary["skipped"], 3);
+    assert_eq!(summary["skipped_by_reason"]["size_limit"], 1);
+    let reasons: Vec<&str> = summary["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["skip_reason"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        reasons,
+        vec!["skip_by_default", "size_limit", "never_search"]
+    );
+    Ok(())
+}
+
+#[test]
+fn binary_files_are_listed_only_with_show_skipped() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = common::corpus([
+        ("notes.txt", b"needle\n".as_slice()),
+        (
+            "blob.txt",
+            b"needle\n\n\x01\0\0\x01\x01\0\0\x01\x01\0\0\x01".as_slice(),
+        ),
+    ])?;
+    rfgrep()
+        .args(["search", "needle", "--"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("1 file skipped: 1 binary\n"))
+        .stderr(predicate::str::contains("blob.txt").not());
+    rfgrep()
+        .args(["search", "needle", "--show-skipped", "--"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("blob.txt: binary file (skipped)"));
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn search_reads_standard_input() {
+    let mut cmd = Command::cargo_bin("rfgrep").unwrap();
+    cmd.arg("search")
+        .arg("needle")
+        .arg("--")
+        .arg("-")
+        .write_stdin("hay\nneedle in hay\nhay\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("(standard input):2:"));
+}
+
+#[test]
+fn files_from_nul_separated_list() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let first = temp_dir.path().join("first.txt");
+    let second = temp_dir.path().join("second.txt");
+    let unlisted = temp_dir.path().join("unlisted.txt");
+    fs::write(&first, "needle\n")?;
+    fs::write(&second, "no match here\n")?;
+    fs::write(&unlisted, "needle\n")?;
+
+    let list = format!("{}\0{}\0", first.display(), second.display());
+
+    let mut cmd = Command::cargo_bin("rfgrep")?;
+    cmd.arg("search")
+        .arg("needle")
+        .arg("--files-from")
+        .arg("-")
+        .arg("-0")
+        .arg("-l")
+        .write_stdin(list);
+    cmd.assert()
+        .success()
+        .stdout(format!("{}\0", first.display()));
+
+    Ok(())
+}
+
+#[test]
+fn stdin_mixed_with_other_roots_is_an_error() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("a.txt"), "needle\n")?;
+
+    let mut cmd = Command::cargo_bin("rfgrep")?;
+    cmd.arg("search")
+        .arg("needle")
+        .arg("--")
+        .arg("-")
+        .arg(temp_dir.path())
+        .write_stdin("needle\n");
+    cmd.assert()
+        .code(2)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "cannot be combined with other search paths",
+        ));
+    Ok(())
+}
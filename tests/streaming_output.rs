@@ -0,0 +1,75 @@
+mod common;
+
+use assert_cmd::Command;
+use tempfile::TempDir;
+
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    common::corpus((0..20).map(|i| {
+        (
+            format!("file{i:02}.txt"),
+            format!("needle {i}\nhay\nneedle again\n"),
+        )
+    }))
+}
+
+#[test]
+fn streamed_output_keeps_files_grouped() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+
+    let output = Command::cargo_bin("rfgrep")?
+        .arg("search")
+        .arg("needle")
+        .arg("--recursive")
+        .arg("--ndjson")
+        .arg("--")
+        .arg(temp_dir.path())
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+
+    let values = String::from_utf8(output)?
+        .lines()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .collect::<Result<Vec<_>, _>>()?;
+    let (summary, matches) = values.split_last().unwrap();
+    assert_eq!(summary["summary"]["matches"], 40);
+    assert_eq!(matches.len(), 40);
+    for pair in matches.chunks(2) {
+        assert_eq!(pair[0]["path"], pair[1]["path"]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sort_path_orders_results() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+
+    let output = Command::cargo_bin("rfgrep")?
+        .arg("search")
+        .arg("needle")
+        .arg("--recursive")
+        .arg("--sort")
+        .arg("path")
+        .arg("-l")
+        .arg("--")
+        .arg(temp_dir.path())
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+
+    let files: Vec<String> = String::from_utf8(output)?
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let mut sorted = files.clone();
+    sorted.sort();
+    assert_eq!(files.len(), 20);
+    assert_eq!(files, sorted);
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::matched_files;
+use tempfile::TempDir;
+
+fn corpus() -> Result<TempDir, Box<dyn std::error::Error>> {
+    common::corpus([
+        ("top.txt", "needle\n"),
+        ("a/one.txt", "needle\n"),
+        ("a/b/two.txt", "needle\n"),
+        ("a/b/c/three.txt", "needle\n"),
+    ])
+}
+
+#[test]
+fn max_depth_limits_the_walk() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+    let root = temp_dir.path();
+
+    assert_eq!(matched_files(&["--max-depth", "1"], root), vec!["top.txt"]);
+    assert_eq!(
+        matched_files(&["--max-depth", "3"], root),
+        vec!["a/b/two.txt", "a/one.txt", "top.txt"]
+    );
+    Ok(())
+}
+
+#[test]
+fn one_file_system_keeps_same_device_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = corpus()?;
+
+    assert_eq!(
+        matched_files(&["--one-file-system"], temp_dir.path()),
+        vec!["a/b/c/three.txt", "a/b/two.txt", "a/one.txt", "top.txt"]
+    );
+    Ok(())
+}
@@ -12,6 +12,7 @@ fn worker_timeout_enforced() {
         .arg("--")
         .arg("bench_data/file1.txt");
     cmd.assert()
-        .success()
-        .stdout(predicate::str::contains("No matches found"));
+        .code(2)
+        .stdout(predicate::str::contains("No matches found"))
+        .stderr(predicate::str::contains("timed out after 1s (timeout)"));
 }